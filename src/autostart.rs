@@ -0,0 +1,194 @@
+use crate::config::{AutostartProgram, Config, Restart};
+use crate::process;
+use crate::property::intern_atom;
+use crate::screen::get_screen;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const PROPERTY: &str = "_MWM_AUTOSTART";
+
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+// A process running at least this long is considered healthy and resets the backoff
+const HEALTHY_RUN: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Debug)]
+enum Status {
+    Pending,
+    Running(Option<u32>),
+    Exited(Option<i32>),
+    Restarting(Duration),
+    Failed,
+    Skipped,
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Pending => write!(f, "pending"),
+            Self::Running(Some(pid)) => write!(f, "running pid={}", pid),
+            Self::Running(None) => write!(f, "running"),
+            Self::Exited(Some(code)) => write!(f, "exited code={}", code),
+            Self::Exited(None) => write!(f, "exited signal"),
+            Self::Restarting(delay) => write!(f, "restarting in {}s", delay.as_secs()),
+            Self::Failed => write!(f, "failed"),
+            Self::Skipped => write!(f, "skipped"),
+        }
+    }
+}
+
+// Tracks the status of autostart programs, published as a root window property
+struct Supervisor {
+    conn: Arc<xcb_util::ewmh::Connection>,
+    config: Arc<Config>,
+    atom: xcb::Atom,
+    utf8_string: xcb::Atom,
+    root: xcb::Window,
+    statuses: Mutex<Vec<(String, Status)>>,
+}
+
+impl Supervisor {
+    fn set_status(&self, index: usize, status: Status) {
+        let mut statuses = self.statuses.lock().unwrap();
+
+        tracing::info!(
+            "autostart status; command={}; status={}",
+            statuses[index].0,
+            status
+        );
+
+        statuses[index].1 = status;
+
+        let value = statuses
+            .iter()
+            .map(|(name, status)| format!("{}: {}\0", name, status))
+            .collect::<String>();
+
+        xcb::change_property(
+            &self.conn,
+            xcb::PROP_MODE_REPLACE as u8,
+            self.root,
+            self.atom,
+            self.utf8_string,
+            8,
+            value.as_bytes(),
+        );
+        self.conn.flush();
+    }
+}
+
+fn display_name(program: &AutostartProgram) -> String {
+    match &program.command {
+        crate::config::CommandLine::Shell(line) => line.clone(),
+        crate::config::CommandLine::Args(args) => args.join(" "),
+    }
+}
+
+/// Starts the configured autostart programs in order, supervising the ones that restart.
+pub fn start(conn: Arc<xcb_util::ewmh::Connection>, config: Arc<Config>) {
+    let root = get_screen(&conn).root();
+    let atom = intern_atom(&conn, PROPERTY);
+    let utf8_string = intern_atom(&conn, "UTF8_STRING");
+
+    // The property outlives the window manager, so finding it means we were restarted in place
+    let restarted = xcb::get_property(&conn, false, root, atom, xcb::ATOM_ANY, 0, 0)
+        .get_reply()
+        .map(|reply| reply.type_() != xcb::ATOM_NONE)
+        .unwrap_or(false);
+
+    let programs = config
+        .autostart
+        .iter()
+        .map(|autostart| autostart.program())
+        .collect::<Vec<AutostartProgram>>();
+
+    let statuses = programs
+        .iter()
+        .map(|program| (display_name(program), Status::Pending))
+        .collect();
+
+    let supervisor = Arc::new(Supervisor {
+        conn,
+        config,
+        atom,
+        utf8_string,
+        root,
+        statuses: Mutex::new(statuses),
+    });
+
+    tokio::spawn(async move {
+        for (index, program) in programs.into_iter().enumerate() {
+            if program.once && restarted {
+                supervisor.set_status(index, Status::Skipped);
+                continue;
+            }
+
+            if program.delay > 0 {
+                tokio::time::sleep(Duration::from_millis(program.delay)).await;
+            }
+
+            tokio::spawn(supervise(supervisor.clone(), index, program));
+        }
+    });
+}
+
+async fn supervise(supervisor: Arc<Supervisor>, index: usize, program: AutostartProgram) {
+    let envs = program
+        .env
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.clone()))
+        .collect::<Vec<(&str, String)>>();
+
+    let mut backoff = MIN_BACKOFF;
+
+    loop {
+        let started = Instant::now();
+
+        let success = match process::spawn(
+            &program.command,
+            &envs,
+            program.cwd.as_deref(),
+            supervisor.config.command_log.as_deref(),
+        ) {
+            Ok(mut child) => {
+                supervisor.set_status(index, Status::Running(child.id()));
+
+                match child.wait().await {
+                    Ok(status) => {
+                        supervisor.set_status(index, Status::Exited(status.code()));
+                        status.success()
+                    }
+                    Err(e) => {
+                        tracing::error!("unable to wait for autostart command; error={}", e);
+                        supervisor.set_status(index, Status::Failed);
+                        false
+                    }
+                }
+            }
+            Err(e) => {
+                tracing::error!("{:#}", e);
+                supervisor.set_status(index, Status::Failed);
+                false
+            }
+        };
+
+        let restart = match program.restart {
+            Restart::Never => false,
+            Restart::OnFailure => !success,
+            Restart::Always => true,
+        };
+
+        if !restart {
+            return;
+        }
+
+        if started.elapsed() >= HEALTHY_RUN {
+            backoff = MIN_BACKOFF;
+        }
+
+        supervisor.set_status(index, Status::Restarting(backoff));
+        tokio::time::sleep(backoff).await;
+
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
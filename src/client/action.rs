@@ -1,52 +1,88 @@
 use crate::client::{Client, Clients};
-use crate::config::Action;
+use crate::config::{Action, Orientation};
+
+// Factor a window's weight changes by when grown or shrunk
+const WEIGHT_STEP: f32 = 1.25;
+const MIN_WEIGHT: f32 = 0.2;
+const MAX_WEIGHT: f32 = 5.0;
 
 impl Clients {
+    // Ask a window to close with WM_DELETE_WINDOW, returns false when it is not supported
+    pub fn send_delete_window(&self, window: xcb::Window) -> bool {
+        let delete_window = xcb::intern_atom(&self.conn, false, "WM_DELETE_WINDOW")
+            .get_reply()
+            .unwrap();
+
+        let reply = xcb_util::icccm::get_wm_protocols(&self.conn, window, self.conn.WM_PROTOCOLS())
+            .get_reply();
+
+        let supports_wm_delete_window = reply
+            .map(|reply| reply.atoms().contains(&delete_window.atom()))
+            .unwrap_or(false);
+
+        if !supports_wm_delete_window {
+            return false;
+        }
+
+        let event = xcb::ClientMessageEvent::new(
+            32,
+            window,
+            self.conn.WM_PROTOCOLS(),
+            xcb::ClientMessageData::from_data32([delete_window.atom(), xcb::CURRENT_TIME, 0, 0, 0]),
+        );
+
+        xcb::send_event_checked(&self.conn, true, window, xcb::EVENT_MASK_NO_EVENT, &event)
+            .request_check()
+            .is_ok()
+    }
+
+    pub fn close(&mut self, window: xcb::Window) {
+        if !self.send_delete_window(window) {
+            xcb::set_close_down_mode(&self.conn, xcb::CLOSE_DOWN_DESTROY_ALL as u8);
+            xcb::kill_client(&self.conn, window);
+        }
+
+        self.conn.flush();
+    }
+
     pub fn handle_action(&mut self, _window: xcb::Window, action: Action) {
         // Handle close action
         if let (Action::Close, Some(window)) = (&action, self.active_window()) {
-            let delete_window = xcb::intern_atom(&self.conn, false, "WM_DELETE_WINDOW")
-                .get_reply()
-                .unwrap();
-
-            let reply =
-                xcb_util::icccm::get_wm_protocols(&self.conn, window, self.conn.WM_PROTOCOLS())
-                    .get_reply();
-
-            let mut supports_wm_delete_window =
-                reply.unwrap().atoms().contains(&delete_window.atom());
-
-            if supports_wm_delete_window {
-                let event = xcb::ClientMessageEvent::new(
-                    32,
-                    window,
-                    self.conn.WM_PROTOCOLS(),
-                    xcb::ClientMessageData::from_data32([
-                        delete_window.atom(),
-                        xcb::CURRENT_TIME,
-                        0,
-                        0,
-                        0,
-                    ]),
-                );
-
-                supports_wm_delete_window = xcb::send_event_checked(
-                    &self.conn,
-                    true,
-                    window,
-                    xcb::EVENT_MASK_NO_EVENT,
-                    &event,
-                )
-                .request_check()
-                .is_ok();
-            }
+            self.close(window);
+        }
 
-            if !supports_wm_delete_window {
-                xcb::set_close_down_mode(&self.conn, xcb::CLOSE_DOWN_DESTROY_ALL as u8);
-                xcb::kill_client(&self.conn, window);
+        match action {
+            Action::FocusLast => return self.focus_last(),
+            Action::CycleWindows => return self.cycle_windows(true),
+            Action::CycleWindowsReverse => return self.cycle_windows(false),
+            Action::Zoom => return self.zoom(),
+            Action::MoveUp => return self.move_up(),
+            Action::MoveDown => return self.move_down(),
+            Action::RotateStackClockwise => return self.rotate_stack(true),
+            Action::RotateStackCounterClockwise => return self.rotate_stack(false),
+            Action::Minimize => {
+                if let Some(window) = self.active_window() {
+                    self.minimize(window);
+                }
+                return;
             }
-
-            self.conn.flush();
+            Action::RestoreLast => return self.restore_last(),
+            Action::FocusUrgent => return self.focus_urgent(),
+            Action::ToggleFullscreen => {
+                if let Some(window) = self.active_window() {
+                    self.set_full_screen(window, None, true);
+                }
+                return;
+            }
+            Action::ToggleSticky => {
+                if let Some(window) = self.active_window() {
+                    self.set_sticky(window, None, true);
+                }
+                return;
+            }
+            Action::FocusDirection(direction) => return self.focus_direction(direction),
+            Action::SwapDirection(direction) => return self.swap_direction(direction),
+            _ => (),
         }
 
         let clients = self
@@ -85,6 +121,10 @@ impl Clients {
         let active_client = clients.get(new_pos);
         if let Some(client) = active_client {
             self.set_active_window(Some(client.window));
+
+            if new_pos != pos {
+                self.warp_pointer(client.window);
+            }
         }
 
         // Handle the window sizing actions
@@ -107,6 +147,57 @@ impl Clients {
             _ => (),
         };
 
+        let active_window = self.active_window();
+        let active_workspace = self.active_workspace;
+
+        for client in self.clients.iter_mut() {
+            match action {
+                Action::GrowWindow if Some(client.window) == active_window => {
+                    client.weight = (client.weight * WEIGHT_STEP).min(MAX_WEIGHT);
+                }
+                Action::ShrinkWindow if Some(client.window) == active_window => {
+                    client.weight = (client.weight / WEIGHT_STEP).max(MIN_WEIGHT);
+                }
+                Action::EqualizeStack if client.workspace == Some(active_workspace) => {
+                    client.weight = 1.0;
+                }
+                _ => (),
+            }
+        }
+
+        let count = self
+            .front_window_count
+            .entry(self.active_workspace)
+            .or_insert(1);
+
+        match action {
+            // No more front windows than there are tiled windows to fill them
+            Action::IncMaster => *count = (*count + 1).min(clients.len()),
+            Action::DecMaster => *count = count.saturating_sub(1),
+            _ => (),
+        };
+
+        let orientation = self
+            .orientation
+            .entry(self.active_workspace)
+            .or_insert(self.config.orientation);
+
+        *orientation = match action {
+            Action::OrientLeft => Orientation::Left,
+            Action::OrientRight => Orientation::Right,
+            Action::OrientTop => Orientation::Top,
+            Action::OrientBottom => Orientation::Bottom,
+            Action::OrientCenter => Orientation::Center,
+            Action::NextOrientation => match orientation {
+                Orientation::Left => Orientation::Right,
+                Orientation::Right => Orientation::Top,
+                Orientation::Top => Orientation::Bottom,
+                Orientation::Bottom => Orientation::Center,
+                Orientation::Center => Orientation::Left,
+            },
+            _ => *orientation,
+        };
+
         self.resize();
     }
 }
@@ -1,9 +1,20 @@
-#[derive(Clone, Eq, PartialEq)]
+use crate::client::SizeHints;
+
+#[derive(Clone, PartialEq)]
 pub struct Client {
     pub window: xcb::Window,
     pub workspace: Option<u8>,
     pub visible: bool,
     pub controlled: bool, // If should resize/size/configure window
+    pub focusable: bool,
     pub full_screen: bool,
+    pub minimized: bool,
+    pub sticky: bool,                       // Shown on every workspace
+    pub urgent: bool,                       // Asking for attention
+    pub transient_for: Option<xcb::Window>, // Parent of a dialog or other transient window
+    pub states: Vec<xcb::Atom>,             // Other _NET_WM_STATE atoms
+    pub restore_geometry: Option<[u32; 4]>, // Position and size before maximizing
+    pub size_hints: Option<SizeHints>,
     pub padding_top: u32,
+    pub weight: f32, // Share of its column or row relative to other windows
 }
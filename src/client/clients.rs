@@ -1,7 +1,17 @@
-use crate::client::Client;
-use crate::config::Config;
+use crate::client::{Client, Launch};
+use crate::config::{Config, Orientation};
+use crate::event::Event;
 use std::collections::{HashMap, VecDeque};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+// _NET_WM_DESKTOP of windows shown on every workspace
+pub const STICKY_DESKTOP: u32 = 0xFFFFFFFF;
+
+// Windows being cycled through, in most recently used order
+pub struct FocusCycle {
+    pub windows: Vec<xcb::Window>,
+    pub index: usize,
+}
 
 pub struct Clients {
     pub conn: Arc<xcb_util::ewmh::Connection>,
@@ -11,9 +21,34 @@ pub struct Clients {
     pub active_window: HashMap<u8, Option<xcb::Window>>,
     pub dock_window: Option<xcb::Window>,
     pub front_window_ratio: HashMap<u8, f32>,
+    // Number of windows sharing the front column
+    pub front_window_count: HashMap<u8, usize>,
+    pub orientation: HashMap<u8, Orientation>,
+    // Tiled windows sharing a cell because they did not fit
+    pub overlapping_windows: Vec<xcb::Window>,
+    // Most recently focused windows first
+    pub focus_history: VecDeque<xcb::Window>,
+    pub focus_cycle: Option<FocusCycle>,
+    // Minimized windows, the most recently minimized last
+    pub minimized: Vec<xcb::Window>,
+    // Urgent windows, the most recently urgent last
+    pub urgent: Vec<xcb::Window>,
+    // Time of the latest key or button press seen
+    pub user_time: Option<xcb::Timestamp>,
+    // Commands launched recently, to place their windows on the workspace they came from
+    pub launches: Vec<Launch>,
+    pub launch_count: u32,
+    // Unmaps done by the window manager not yet seen, other unmaps withdraw the window
+    pub pending_unmaps: HashMap<xcb::Window, u32>,
+    pub events: Vec<Event>,
 }
 
 impl Clients {
+    // Lock the clients, recovering them after a plugin panicked while holding the lock
+    pub fn lock(clients: &Mutex<Clients>) -> MutexGuard<'_, Clients> {
+        clients.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
     pub fn new(conn: Arc<xcb_util::ewmh::Connection>, config: Arc<Config>) -> Self {
         Self {
             conn,
@@ -23,6 +58,18 @@ impl Clients {
             active_window: HashMap::new(),
             dock_window: None,
             front_window_ratio: HashMap::new(),
+            front_window_count: HashMap::new(),
+            orientation: HashMap::new(),
+            overlapping_windows: Vec::new(),
+            focus_history: VecDeque::new(),
+            focus_cycle: None,
+            minimized: Vec::new(),
+            urgent: Vec::new(),
+            user_time: None,
+            launches: Vec::new(),
+            launch_count: 0,
+            pending_unmaps: HashMap::new(),
+            events: Vec::new(),
         }
     }
 
@@ -35,4 +82,12 @@ impl Clients {
 
         self.set_workspace_names();
     }
+
+    pub fn emit(&mut self, event: Event) {
+        self.events.push(event);
+    }
+
+    pub fn take_events(&mut self) -> Vec<Event> {
+        std::mem::take(&mut self.events)
+    }
 }
@@ -1,4 +1,7 @@
-use crate::client::{Client, Clients};
+use crate::client::{Client, Clients, SizeHints, WindowBehavior, STICKY_DESKTOP};
+use crate::config::WindowType;
+use crate::event::{Event, WindowCreatedEvent};
+use crate::property::{get_wm_transient_for, set_wm_state, WM_STATE_NORMAL};
 
 impl Clients {
     pub fn create(&mut self, window: xcb::Window) {
@@ -9,26 +12,41 @@ impl Clients {
             return;
         }
 
-        self.enable_event_mask(window);
+        // Transient windows float on the workspace of their parent
+        let parent = get_wm_transient_for(&self.conn, window)
+            .and_then(|parent| self.clients.iter().find(|c| c.window == parent))
+            .map(|parent| (parent.window, parent.workspace, parent.sticky));
 
-        let reply = xcb_util::ewmh::get_wm_window_type(&self.conn, window).get_reply();
+        let size_hints = SizeHints::get(&self.conn, window);
 
-        let mut controlled = true;
+        let window_type = self.window_type(window);
+        let mut behavior = WindowBehavior::for_type(window_type);
 
-        if let Ok(window_type) = reply {
-            let atoms = window_type.atoms();
-            for atom in atoms {
-                if *atom == self.conn.WM_WINDOW_TYPE_DOCK() {
-                    self.dock_window = Some(window);
-                    controlled = false;
-                }
+        // Windows that can not be resized float
+        if parent.is_some() || matches!(size_hints, Some(hints) if hints.is_fixed()) {
+            behavior.floating = true;
+        }
 
-                if *atom == self.conn.WM_WINDOW_TYPE_DIALOG() {
-                    controlled = false;
-                }
-            }
+        self.apply_rules(window, window_type, &mut behavior);
+
+        // Menus, tooltips and notifications are shown as they are
+        if !behavior.managed {
+            tracing::debug!("window not managed; window={}", window);
+
+            xcb::map_window(&self.conn, window);
+            self.conn.flush();
+
+            return;
         }
 
+        self.enable_event_mask(window);
+
+        if window_type == WindowType::Dock {
+            self.dock_window = Some(window);
+        }
+
+        let controlled = !behavior.floating;
+
         let cookie = xcb_util::ewmh::get_wm_strut_partial(&self.conn, window).get_reply();
 
         // TODO: Add other paddings
@@ -38,19 +56,58 @@ impl Clients {
             0
         };
 
-        let workspace = if controlled {
-            Some(self.active_workspace)
-        } else {
-            None
+        // Keep the desktop and sticky state set by clients or a previous window manager
+        let desktop = xcb_util::ewmh::get_wm_desktop(&self.conn, window)
+            .get_reply()
+            .ok();
+
+        let sticky = matches!(parent, Some((_, _, true)))
+            || desktop == Some(STICKY_DESKTOP)
+            || xcb_util::ewmh::get_wm_state(&self.conn, window)
+                .get_reply()
+                .map(|state| state.atoms().contains(&self.conn.WM_STATE_STICKY()))
+                .unwrap_or(false);
+
+        // Windows of launched commands open on the workspace they were launched from
+        let launch_workspace = self.launch_workspace(window);
+
+        let workspace = match desktop {
+            _ if behavior.global => None,
+            _ if behavior.workspace.is_some() => behavior.workspace,
+            _ if parent.is_some() => parent.and_then(|(_, workspace, _)| workspace),
+            _ if launch_workspace.is_some() => launch_workspace,
+            Some(desktop) if (1..=9).contains(&desktop) => Some(desktop as u8),
+            _ => Some(self.active_workspace),
         };
 
+        let mut states = self.load_states(window);
+
+        if behavior.below && !states.contains(&self.conn.WM_STATE_BELOW()) {
+            states.push(self.conn.WM_STATE_BELOW());
+        }
+        let full_screen = xcb_util::ewmh::get_wm_state(&self.conn, window)
+            .get_reply()
+            .map(|state| state.atoms().contains(&self.conn.WM_STATE_FULLSCREEN()))
+            .unwrap_or(false);
+
+        let visible = workspace.is_none() || workspace == Some(self.active_workspace) || sticky;
+
         self.clients.push_front(Client {
             window,
             workspace,
-            visible: true,
+            visible,
             controlled,
+            focusable: behavior.focusable,
             full_screen: false,
+            minimized: false,
+            sticky,
+            urgent: false,
+            transient_for: parent.map(|(parent, _, _)| parent),
+            states,
+            restore_geometry: None,
+            size_hints,
             padding_top,
+            weight: 1.0,
         });
 
         // Make sure window does not overlap with statusbar
@@ -60,19 +117,30 @@ impl Clients {
                 window,
                 &[(xcb::CONFIG_WINDOW_Y as u16, self.get_padding_top() as u32)],
             );
+        } else if parent.is_some() || behavior.center {
+            self.center_window(window);
+        }
+
+        if visible {
+            xcb::map_window(&self.conn, window);
         }
 
-        xcb::map_window(&self.conn, window);
+        set_wm_state(&self.conn, window, WM_STATE_NORMAL);
+        self.set_wm_desktop(window);
+        self.set_net_wm_state(window);
 
         // Ensure border width and color is set for non-dock windows
         if self.dock_window != Some(window) {
+            let border = if behavior.border {
+                self.config.border_thickness
+            } else {
+                0
+            };
+
             xcb::configure_window(
                 &self.conn,
                 window,
-                &[(
-                    xcb::CONFIG_WINDOW_BORDER_WIDTH as u16,
-                    self.config.border_thickness,
-                )],
+                &[(xcb::CONFIG_WINDOW_BORDER_WIDTH as u16, border)],
             );
 
             xcb::change_window_attributes(
@@ -81,14 +149,43 @@ impl Clients {
                 &[(xcb::CW_BORDER_PIXEL, self.config.inactive_border)],
             );
 
-            // Set window as active
-            self.set_active_window(Some(window));
+            self.grab_buttons(window);
+
+            // Set window as active, unless opened while the user was busy with another window
+            if visible {
+                let transient_for = parent.map(|(parent, _, _)| parent);
+
+                if self.allow_focus(window, transient_for, behavior.steal_focus) {
+                    self.set_active_window(Some(window));
+                } else {
+                    tracing::debug!("focus stealing prevented; window={}", window);
+
+                    self.set_state(
+                        window,
+                        self.conn.WM_STATE_DEMANDS_ATTENTION(),
+                        Some(true),
+                        false,
+                    );
+                }
+            }
+        }
+
+        if full_screen {
+            self.set_full_screen(window, Some(true), false);
         }
 
+        self.restack(window);
+        self.update_urgency(window);
+
         self.conn.flush();
 
         self.refresh_clients();
 
+        self.emit(Event::WindowCreated(WindowCreatedEvent {
+            window,
+            workspace,
+        }));
+
         tracing::debug!(
             "client created; controlled={}; window={}",
             controlled,
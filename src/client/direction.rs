@@ -0,0 +1,335 @@
+use crate::client::Clients;
+use crate::config::Direction;
+
+// Pixels windows can overlap and still be considered neighbours
+const OVERLAP_TOLERANCE: i32 = 8;
+
+#[derive(Clone, Copy)]
+struct Geometry {
+    left: i32,
+    top: i32,
+    right: i32,
+    bottom: i32,
+}
+
+impl Geometry {
+    // Distance to another window along a direction, none when it is not in that direction
+    fn distance(&self, other: &Geometry, direction: Direction) -> Option<i32> {
+        let distance = match direction {
+            Direction::Left => self.left - other.right,
+            Direction::Right => other.left - self.right,
+            Direction::Up => self.top - other.bottom,
+            Direction::Down => other.top - self.bottom,
+        };
+
+        if distance >= -OVERLAP_TOLERANCE {
+            Some(distance.max(0))
+        } else {
+            None
+        }
+    }
+
+    // Gap between the windows across a direction, zero when they are side by side
+    fn offset(&self, other: &Geometry, direction: Direction) -> i32 {
+        let (start, end, other_start, other_end) = match direction {
+            Direction::Left | Direction::Right => (self.top, self.bottom, other.top, other.bottom),
+            Direction::Up | Direction::Down => (self.left, self.right, other.left, other.right),
+        };
+
+        (other_start - end).max(start - other_end).max(0)
+    }
+
+    fn center_offset(&self, other: &Geometry, direction: Direction) -> i32 {
+        match direction {
+            Direction::Left | Direction::Right => {
+                ((self.top + self.bottom) - (other.top + other.bottom)).abs() / 2
+            }
+            Direction::Up | Direction::Down => {
+                ((self.left + self.right) - (other.left + other.right)).abs() / 2
+            }
+        }
+    }
+}
+
+// The candidate nearest to the current window in a direction, the first one on a tie
+fn nearest<T>(
+    current: &Geometry,
+    candidates: impl Iterator<Item = (Geometry, T)>,
+    direction: Direction,
+) -> Option<T> {
+    candidates
+        .filter_map(|(geometry, candidate)| {
+            let distance = current.distance(&geometry, direction)?;
+
+            // Prefer windows beside the current one, then the closest, then the most aligned
+            let score = (
+                current.offset(&geometry, direction),
+                distance,
+                current.center_offset(&geometry, direction),
+            );
+
+            Some((score, candidate))
+        })
+        .min_by_key(|(score, _)| *score)
+        .map(|(_, candidate)| candidate)
+}
+
+impl Clients {
+    fn get_geometry(&self, window: xcb::Window) -> Option<Geometry> {
+        let reply = xcb::get_geometry(&self.conn, window).get_reply().ok()?;
+
+        let border = reply.border_width() as i32 * 2;
+        let left = reply.x() as i32;
+        let top = reply.y() as i32;
+
+        Some(Geometry {
+            left,
+            top,
+            right: left + reply.width() as i32 + border,
+            bottom: top + reply.height() as i32 + border,
+        })
+    }
+
+    // The nearest visible window in a direction, based on where windows are on screen
+    pub fn window_in_direction(
+        &mut self,
+        direction: Direction,
+        tiled_only: bool,
+    ) -> Option<xcb::Window> {
+        let active_window = self.active_window()?;
+        let current = self.get_geometry(active_window)?;
+
+        let candidates = self
+            .clients
+            .iter()
+            .filter(|c| c.window != active_window && c.visible)
+            .filter(|c| Some(c.window) != self.dock_window)
+            .filter(|c| !tiled_only || c.controlled)
+            .filter_map(|c| Some((self.get_geometry(c.window)?, c.window)));
+
+        nearest(&current, candidates, direction)
+    }
+
+    pub fn focus_direction(&mut self, direction: Direction) {
+        if let Some(window) = self.window_in_direction(direction, false) {
+            tracing::debug!(
+                "focusing in direction; direction={:?}; window={}",
+                direction,
+                window
+            );
+
+            self.set_active_window(Some(window));
+            self.warp_pointer(window);
+        }
+    }
+
+    // Swap the focused window with the nearest tiled window in a direction
+    pub fn swap_direction(&mut self, direction: Direction) {
+        let active_window = match self.active_window() {
+            Some(window) => window,
+            None => return,
+        };
+
+        let window = match self.window_in_direction(direction, true) {
+            Some(window) => window,
+            None => return,
+        };
+
+        let a = self.clients.iter().position(|c| c.window == active_window);
+        let b = self.clients.iter().position(|c| c.window == window);
+
+        if let (Some(a), Some(b)) = (a, b) {
+            if !self.clients[a].controlled {
+                return;
+            }
+
+            tracing::debug!(
+                "swapping in direction; direction={:?}; window={}",
+                direction,
+                window
+            );
+
+            self.clients.swap(a, b);
+            self.resize();
+            self.warp_pointer(active_window);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIRECTIONS: [Direction; 4] = [
+        Direction::Left,
+        Direction::Right,
+        Direction::Up,
+        Direction::Down,
+    ];
+
+    fn geometry(left: i32, top: i32, width: i32, height: i32) -> Geometry {
+        Geometry {
+            left,
+            top,
+            right: left + width,
+            bottom: top + height,
+        }
+    }
+
+    #[test]
+    fn distance_to_neighbours() {
+        let current = geometry(100, 100, 100, 100);
+
+        assert_eq!(
+            current.distance(&geometry(0, 100, 90, 100), Direction::Left),
+            Some(10)
+        );
+        assert_eq!(
+            current.distance(&geometry(210, 100, 90, 100), Direction::Right),
+            Some(10)
+        );
+        assert_eq!(
+            current.distance(&geometry(100, 0, 100, 90), Direction::Up),
+            Some(10)
+        );
+        assert_eq!(
+            current.distance(&geometry(100, 210, 100, 90), Direction::Down),
+            Some(10)
+        );
+    }
+
+    #[test]
+    fn distance_with_overlap() {
+        let current = geometry(100, 100, 100, 100);
+
+        // Overlapping by less than the tolerance, as borders of tiled windows do
+        let touching = geometry(200 - OVERLAP_TOLERANCE, 100, 100, 100);
+        assert_eq!(current.distance(&touching, Direction::Right), Some(0));
+
+        let overlapping = geometry(200 - OVERLAP_TOLERANCE - 1, 100, 100, 100);
+        assert_eq!(current.distance(&overlapping, Direction::Right), None);
+    }
+
+    #[test]
+    fn distance_in_other_direction() {
+        let current = geometry(100, 100, 100, 100);
+        let right = geometry(210, 100, 100, 100);
+
+        assert_eq!(current.distance(&right, Direction::Left), None);
+        assert_eq!(current.distance(&right, Direction::Up), None);
+        assert_eq!(current.distance(&right, Direction::Down), None);
+    }
+
+    #[test]
+    fn offset_across_direction() {
+        let current = geometry(100, 100, 100, 100);
+
+        // Side by side
+        assert_eq!(
+            current.offset(&geometry(210, 150, 100, 100), Direction::Right),
+            0
+        );
+        // Below the current window
+        assert_eq!(
+            current.offset(&geometry(210, 220, 100, 100), Direction::Right),
+            20
+        );
+        // Above the current window
+        assert_eq!(
+            current.offset(&geometry(210, 0, 100, 80), Direction::Right),
+            20
+        );
+        assert_eq!(
+            current.offset(&geometry(230, 210, 100, 100), Direction::Down),
+            30
+        );
+    }
+
+    #[test]
+    fn center_offset_across_direction() {
+        let current = geometry(100, 100, 100, 100);
+
+        assert_eq!(
+            current.center_offset(&geometry(210, 100, 100, 100), Direction::Right),
+            0
+        );
+        assert_eq!(
+            current.center_offset(&geometry(210, 140, 100, 100), Direction::Right),
+            40
+        );
+        assert_eq!(
+            current.center_offset(&geometry(60, 210, 100, 100), Direction::Down),
+            40
+        );
+    }
+
+    #[test]
+    fn nearest_prefers_side_by_side() {
+        let current = geometry(100, 100, 100, 100);
+        let candidates = vec![
+            // Closer, but below the current window
+            (geometry(205, 300, 100, 100), 1),
+            (geometry(250, 150, 100, 100), 2),
+        ];
+
+        assert_eq!(
+            nearest(&current, candidates.into_iter(), Direction::Right),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn nearest_prefers_closest_then_aligned() {
+        let current = geometry(100, 100, 100, 100);
+        let candidates = vec![
+            (geometry(300, 100, 100, 100), 1),
+            (geometry(210, 150, 100, 100), 2),
+            (geometry(210, 110, 100, 100), 3),
+        ];
+
+        assert_eq!(
+            nearest(&current, candidates.into_iter(), Direction::Right),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn nearest_tie() {
+        let current = geometry(100, 100, 100, 100);
+        let candidates = vec![
+            (geometry(210, 50, 100, 100), 1),
+            (geometry(210, 150, 100, 100), 2),
+        ];
+
+        assert_eq!(
+            nearest(&current, candidates.into_iter(), Direction::Right),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn nearest_without_candidates() {
+        let current = geometry(100, 100, 100, 100);
+
+        for direction in DIRECTIONS {
+            assert_eq!(
+                nearest(
+                    &current,
+                    Vec::<(Geometry, u32)>::new().into_iter(),
+                    direction
+                ),
+                None
+            );
+        }
+
+        // Only a window to the right
+        let candidates = vec![(geometry(210, 100, 100, 100), 1)];
+
+        for direction in [Direction::Left, Direction::Up, Direction::Down] {
+            assert_eq!(
+                nearest(&current, candidates.clone().into_iter(), direction),
+                None
+            );
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use crate::client::{Clients, FocusCycle};
+use crate::key::MODIFIER_MASK;
+use crate::screen::get_screen;
+
+impl Clients {
+    pub fn push_focus_history(&mut self, window: xcb::Window) {
+        self.focus_history.retain(|&w| w != window);
+        self.focus_history.push_front(window);
+    }
+
+    // Controlled windows on a workspace that are not minimized, most recently focused first
+    pub fn workspace_focus_history(&self, workspace: Option<u8>) -> Vec<xcb::Window> {
+        self.focus_history
+            .iter()
+            .filter(|&&window| {
+                self.clients.iter().any(|c| {
+                    c.window == window && c.controlled && !c.minimized && c.workspace == workspace
+                })
+            })
+            .copied()
+            .collect()
+    }
+
+    // Move focus away from a window leaving its workspace, to the window focused before it
+    pub fn refocus(&mut self, window: xcb::Window, workspace: Option<u8>) {
+        let last_window = self
+            .workspace_focus_history(workspace)
+            .into_iter()
+            .find(|&w| w != window);
+
+        if self.active_window() == Some(window) {
+            let active_window = last_window.or_else(|| {
+                self.clients
+                    .iter()
+                    .find(|c| c.window != window && c.controlled && c.visible)
+                    .map(|c| c.window)
+            });
+            self.set_active_window(active_window);
+        } else if let Some(workspace) = workspace {
+            if self.active_window.get(&workspace) == Some(&Some(window)) {
+                self.active_window.insert(workspace, last_window);
+            }
+        }
+    }
+
+    pub fn focus_last(&mut self) {
+        let history = self.workspace_focus_history(Some(self.active_workspace));
+
+        if let Some(&window) = history.get(1) {
+            tracing::debug!("focusing last window; window={}", window);
+
+            self.set_active_window(Some(window));
+            self.warp_pointer(window);
+        }
+    }
+
+    // Focus a window, switching to its workspace if needed
+    pub fn focus_window(&mut self, window: xcb::Window) {
+        let workspace = match self.clients.iter().find(|c| c.window == window) {
+            Some(client) => client.workspace,
+            None => return,
+        };
+
+        if let Some(workspace) = workspace {
+            if workspace != self.active_workspace {
+                self.set_active_workspace(workspace);
+            }
+        }
+
+        self.set_active_window(Some(window));
+        self.warp_pointer(window);
+    }
+
+    // Cycle through windows on all workspaces, the focus history is only updated once the cycle ends
+    pub fn cycle_windows(&mut self, forward: bool) {
+        if self.focus_cycle.is_none() {
+            let windows = self
+                .focus_history
+                .iter()
+                .filter(|&&window| {
+                    self.clients.iter().any(|c| {
+                        c.window == window && c.controlled && !c.minimized && c.workspace.is_some()
+                    })
+                })
+                .copied()
+                .collect::<Vec<xcb::Window>>();
+
+            if windows.len() < 2 {
+                return;
+            }
+
+            tracing::debug!("starting focus cycle; windows={}", windows.len());
+
+            let root = get_screen(&self.conn).root();
+
+            // Grab the keyboard so the release of the modifier is seen
+            xcb::grab_keyboard(
+                &self.conn,
+                false,
+                root,
+                xcb::CURRENT_TIME,
+                xcb::GRAB_MODE_ASYNC as u8,
+                xcb::GRAB_MODE_ASYNC as u8,
+            );
+
+            self.focus_cycle = Some(FocusCycle { windows, index: 0 });
+        }
+
+        let window = match self.focus_cycle.as_mut() {
+            Some(cycle) => {
+                let length = cycle.windows.len();
+
+                cycle.index = if forward {
+                    (cycle.index + 1) % length
+                } else {
+                    (cycle.index + length - 1) % length
+                };
+
+                cycle.windows[cycle.index]
+            }
+            None => return,
+        };
+
+        self.focus_window(window);
+
+        // Nothing to wait for when no modifier is held, such as when cycling from a script
+        let root = get_screen(&self.conn).root();
+        let held = xcb::query_pointer(&self.conn, root)
+            .get_reply()
+            .map(|reply| reply.mask() & MODIFIER_MASK != 0)
+            .unwrap_or(false);
+
+        if !held {
+            self.end_cycle();
+        }
+    }
+
+    pub fn end_cycle(&mut self) {
+        if self.focus_cycle.take().is_none() {
+            return;
+        }
+
+        tracing::debug!("ending focus cycle");
+
+        xcb::ungrab_keyboard(&self.conn, xcb::CURRENT_TIME);
+
+        if let Some(window) = self.active_window() {
+            self.push_focus_history(window);
+        }
+
+        self.conn.flush();
+    }
+}
@@ -1,6 +1,11 @@
 use crate::client::{Client, Clients};
+use crate::config::{Orientation, Overflow};
+use crate::property::set_utf8_property;
 use crate::screen::get_screen;
 
+// Smallest width or height of a tiled window before windows overflow
+const MIN_WINDOW_SIZE: usize = 32;
+
 impl Clients {
     pub fn get_padding_top(&self) -> usize {
         self.clients
@@ -21,51 +26,125 @@ impl Clients {
         let gap = self.config.border_gap as usize;
         let gap_double = gap * 2;
 
-        let padding_top = self.get_padding_top();
+        // A full screen window covers the workspace, the other windows keep their place
+        let full_screen = self
+            .clients
+            .iter()
+            .find(|c| c.visible && c.full_screen && !c.minimized)
+            .map(|c| c.window);
+
+        self.set_dock_hidden(full_screen.is_some() && self.config.full_screen_hides_dock);
+
+        if let Some(window) = full_screen {
+            xcb::configure_window(
+                &self.conn,
+                window,
+                &[
+                    (xcb::CONFIG_WINDOW_X as u16, 0),
+                    (xcb::CONFIG_WINDOW_Y as u16, 0),
+                    (xcb::CONFIG_WINDOW_WIDTH as u16, screen_width as u32),
+                    (xcb::CONFIG_WINDOW_HEIGHT as u16, screen_height as u32),
+                    (xcb::CONFIG_WINDOW_BORDER_WIDTH as u16, 0),
+                    // Above every other window including docks
+                    (xcb::CONFIG_WINDOW_STACK_MODE as u16, xcb::STACK_MODE_ABOVE),
+                ],
+            );
+
+            // Dialogs of the full screen window would otherwise end up hidden behind it
+            self.raise_transients();
+
+            self.set_layout_indicator();
+            self.conn.flush();
+
+            return;
+        }
 
-        let max_clients = (screen_height - padding_top) / (gap_double + border_double) - 1;
+        let padding_top = self.get_padding_top().min(screen_height);
 
         let visible_clients = self
             .clients
             .iter()
             .filter(|&c| c.visible && c.controlled)
-            .take(max_clients)
             .cloned()
             .collect::<Vec<Client>>();
 
-        let clients_length = visible_clients.len();
-        let available_height = screen_height - padding_top;
-
         let front_window_ratio = *self
             .front_window_ratio
             .entry(self.active_workspace)
             .or_insert(0.5);
 
-        // Tile windows
-        for (i, client) in visible_clients.iter().enumerate() {
-            let (mut x, mut y) = (gap, gap + padding_top);
+        let front_window_count = *self
+            .front_window_count
+            .entry(self.active_workspace)
+            .or_insert(1);
 
-            let (mut width, mut height) = (
-                screen_width - border_double - gap_double,
-                available_height - border_double - gap_double,
-            );
+        let area = Rect {
+            x: 0,
+            y: padding_top,
+            width: screen_width,
+            height: screen_height - padding_top,
+        };
 
-            if clients_length > 1 {
-                width -= border_double - gap_double;
+        let min_size = border_double + gap_double + MIN_WINDOW_SIZE;
 
-                let front_window_width = (width as f32 * front_window_ratio) as usize;
-                let window_height = (available_height) / (clients_length - 1);
+        let orientation = *self
+            .orientation
+            .entry(self.active_workspace)
+            .or_insert(self.config.orientation);
+
+        let weights = visible_clients
+            .iter()
+            .map(|c| c.weight)
+            .collect::<Vec<f32>>();
 
-                if i > 0 {
-                    width -= front_window_width;
-                    height = window_height - border_double - gap_double;
+        let mut cells = layout(
+            area,
+            orientation,
+            front_window_ratio,
+            front_window_count,
+            &weights,
+            min_size,
+        );
 
-                    x += front_window_width + border_double + gap_double;
-                    y += window_height * (i - 1);
-                } else {
-                    width = front_window_width;
+        // Windows that did not fit share a cell with other windows
+        let shared = |cells: &[Rect], cell: &Rect| cells.iter().filter(|&c| c == cell).count() > 1;
+
+        if self.config.overflow == Overflow::Monocle && cells.iter().any(|c| shared(&cells, c)) {
+            cells = vec![area; cells.len()];
+        }
+
+        self.overlapping_windows = visible_clients
+            .iter()
+            .zip(&cells)
+            .filter(|(_, cell)| shared(&cells, cell))
+            .map(|(c, _)| c.window)
+            .collect();
+
+        // Tile windows
+        for (client, cell) in visible_clients.iter().zip(cells) {
+            let x = cell.x + gap;
+            let y = cell.y + gap;
+            let width = cell.width.saturating_sub(border_double + gap_double).max(1);
+            let height = cell
+                .height
+                .saturating_sub(border_double + gap_double)
+                .max(1);
+
+            // Keep to the size hints, centring the window in its cell
+            let (x, y, width, height) = match client.size_hints {
+                Some(hints) if !self.config.ignore_size_hints => {
+                    let (w, h) = hints.apply(width as u32, height as u32);
+                    let (w, h) = (w as usize, h as usize);
+
+                    (
+                        x + width.saturating_sub(w) / 2,
+                        y + height.saturating_sub(h) / 2,
+                        w,
+                        h,
+                    )
                 }
-            }
+                _ => (x, y, width, height),
+            };
 
             self.disable_event_mask(client.window);
 
@@ -84,21 +163,384 @@ impl Clients {
             self.enable_event_mask(client.window);
         }
 
-        // Full screen windows
-        for client in visible_clients.iter().filter(|&c| c.full_screen) {
+        if let Some(window) = self.active_window() {
+            self.raise_overlapping(window);
+        }
+
+        self.raise_transients();
+
+        self.set_layout_indicator();
+
+        self.conn.flush();
+    }
+
+    // Unmap the dock while a full screen window is shown, if configured
+    fn set_dock_hidden(&mut self, hidden: bool) {
+        let dock_window = match self.dock_window {
+            Some(window) => window,
+            None => return,
+        };
+
+        if let Some(dock) = self.clients.iter_mut().find(|c| c.window == dock_window) {
+            if hidden && dock.visible {
+                tracing::debug!("hiding dock; window={}", dock_window);
+                dock.visible = false;
+                xcb::unmap_window(&self.conn, dock_window);
+                *self.pending_unmaps.entry(dock_window).or_insert(0) += 1;
+            } else if !hidden && !dock.visible {
+                tracing::debug!("showing dock; window={}", dock_window);
+                dock.visible = true;
+                xcb::map_window(&self.conn, dock_window);
+            }
+        }
+    }
+
+    // Raise a window sharing its cell with others, so the focused one is seen
+    pub fn raise_overlapping(&self, window: xcb::Window) {
+        if self.overlapping_windows.contains(&window) {
             xcb::configure_window(
                 &self.conn,
-                client.window,
-                &[
-                    (xcb::CONFIG_WINDOW_X as u16, 0),
-                    (xcb::CONFIG_WINDOW_Y as u16, 0),
-                    (xcb::CONFIG_WINDOW_WIDTH as u16, screen_width as u32),
-                    (xcb::CONFIG_WINDOW_HEIGHT as u16, screen_height as u32),
-                    (xcb::CONFIG_WINDOW_BORDER_WIDTH as u16, 0),
-                ],
+                window,
+                &[(xcb::CONFIG_WINDOW_STACK_MODE as u16, xcb::STACK_MODE_ABOVE)],
             );
         }
+    }
 
-        self.conn.flush();
+    // Publish a dwm style layout symbol for the active workspace, with the front window count
+    // when it is not one, for use in statusbars
+    pub fn set_layout_indicator(&mut self) {
+        let front_window_count = *self
+            .front_window_count
+            .entry(self.active_workspace)
+            .or_insert(1);
+
+        let orientation = *self
+            .orientation
+            .entry(self.active_workspace)
+            .or_insert(self.config.orientation);
+
+        let symbol = match orientation {
+            Orientation::Left => "[]=",
+            Orientation::Right => "=[]",
+            Orientation::Top => "TTT",
+            Orientation::Bottom => "___",
+            Orientation::Center => "|M|",
+        };
+
+        let symbol = if front_window_count == 1 {
+            symbol.to_string()
+        } else {
+            format!("{} {}", symbol, front_window_count)
+        };
+
+        set_utf8_property(
+            &self.conn,
+            get_screen(&self.conn).root(),
+            "_MWM_LAYOUT",
+            &symbol,
+        );
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Rect {
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
+
+impl Rect {
+    // Split into a left part of the given width and a right part with the rest
+    fn split_x(&self, width: usize) -> (Rect, Rect) {
+        (
+            Rect { width, ..*self },
+            Rect {
+                x: self.x + width,
+                width: self.width.saturating_sub(width),
+                ..*self
+            },
+        )
+    }
+
+    // Split into a top part of the given height and a bottom part with the rest
+    fn split_y(&self, height: usize) -> (Rect, Rect) {
+        (
+            Rect { height, ..*self },
+            Rect {
+                y: self.y + height,
+                height: self.height.saturating_sub(height),
+                ..*self
+            },
+        )
+    }
+
+    // Split into rows with heights proportional to the weights, the last row takes any remainder
+    // and is shared by the windows that do not fit
+    fn split_rows(&self, weights: &[f32], min_height: usize) -> Vec<Rect> {
+        let rows = fit(self.height, min_height, weights.len());
+        let total = weights[..rows].iter().sum::<f32>();
+        let mut y = self.y;
+
+        (0..weights.len())
+            .map(|i| {
+                let i = i.min(rows - 1);
+                let offset = self.y + self.height - y;
+
+                let height = if i == rows - 1 {
+                    offset
+                } else {
+                    ((self.height as f32 * weights[i] / total) as usize).min(offset)
+                };
+
+                let rect = Rect { y, height, ..*self };
+
+                if i < rows - 1 {
+                    y += height;
+                }
+
+                rect
+            })
+            .collect()
+    }
+
+    // Split into columns with widths proportional to the weights, like rows
+    fn split_columns(&self, weights: &[f32], min_width: usize) -> Vec<Rect> {
+        let columns = fit(self.width, min_width, weights.len());
+        let total = weights[..columns].iter().sum::<f32>();
+        let mut x = self.x;
+
+        (0..weights.len())
+            .map(|i| {
+                let i = i.min(columns - 1);
+                let offset = self.x + self.width - x;
+
+                let width = if i == columns - 1 {
+                    offset
+                } else {
+                    ((self.width as f32 * weights[i] / total) as usize).min(offset)
+                };
+
+                let rect = Rect { x, width, ..*self };
+
+                if i < columns - 1 {
+                    x += width;
+                }
+
+                rect
+            })
+            .collect()
+    }
+}
+
+// How many windows of at least the minimum size fit in a length, at least one
+fn fit(length: usize, min_size: usize, count: usize) -> usize {
+    (length / min_size.max(1)).max(1).min(count)
+}
+
+// Cells for each tiled window in order, the front windows first
+fn layout(
+    area: Rect,
+    orientation: Orientation,
+    ratio: f32,
+    front_count: usize,
+    weights: &[f32],
+    min_size: usize,
+) -> Vec<Rect> {
+    let front_count = front_count.min(weights.len());
+    let (front_weights, back_weights) = weights.split_at(front_count);
+
+    let horizontal = matches!(orientation, Orientation::Top | Orientation::Bottom);
+
+    // A single group of windows fills the area
+    if front_weights.is_empty() || back_weights.is_empty() {
+        return if horizontal {
+            area.split_columns(weights, min_size)
+        } else {
+            area.split_rows(weights, min_size)
+        };
+    }
+
+    let front_width = (area.width as f32 * ratio) as usize;
+    let front_height = (area.height as f32 * ratio) as usize;
+
+    let (front_area, back_area) = match orientation {
+        Orientation::Left => area.split_x(front_width),
+        Orientation::Right => {
+            let (back, front) = area.split_x(area.width.saturating_sub(front_width));
+            (front, back)
+        }
+        Orientation::Top => area.split_y(front_height),
+        Orientation::Bottom => {
+            let (back, front) = area.split_y(area.height.saturating_sub(front_height));
+            (front, back)
+        }
+        // Stack windows alternate between the right and left of the front windows
+        Orientation::Center if back_weights.len() > 1 => {
+            let side_width = area.width.saturating_sub(front_width) / 2;
+            let (left_area, rest) = area.split_x(side_width);
+            let (front_area, right_area) = rest.split_x(front_width);
+
+            let right_weights = back_weights.iter().step_by(2).copied();
+            let left_weights = back_weights.iter().skip(1).step_by(2).copied();
+
+            let mut right = right_area
+                .split_rows(&right_weights.collect::<Vec<f32>>(), min_size)
+                .into_iter();
+            let mut left = left_area
+                .split_rows(&left_weights.collect::<Vec<f32>>(), min_size)
+                .into_iter();
+
+            let back = (0..back_weights.len()).filter_map(|i| {
+                if i % 2 == 0 {
+                    right.next()
+                } else {
+                    left.next()
+                }
+            });
+
+            return front_area
+                .split_rows(front_weights, min_size)
+                .into_iter()
+                .chain(back)
+                .collect();
+        }
+        Orientation::Center => area.split_x(front_width),
+    };
+
+    if horizontal {
+        front_area
+            .split_columns(front_weights, min_size)
+            .into_iter()
+            .chain(back_area.split_columns(back_weights, min_size))
+            .collect()
+    } else {
+        front_area
+            .split_rows(front_weights, min_size)
+            .into_iter()
+            .chain(back_area.split_rows(back_weights, min_size))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AREA: Rect = Rect {
+        x: 0,
+        y: 20,
+        width: 1920,
+        height: 1060,
+    };
+
+    const ORIENTATIONS: [Orientation; 5] = [
+        Orientation::Left,
+        Orientation::Right,
+        Orientation::Top,
+        Orientation::Bottom,
+        Orientation::Center,
+    ];
+
+    fn inside(area: Rect, cell: Rect) -> bool {
+        cell.x >= area.x
+            && cell.y >= area.y
+            && cell.x + cell.width <= area.x + area.width
+            && cell.y + cell.height <= area.y + area.height
+    }
+
+    #[test]
+    fn no_windows() {
+        for orientation in ORIENTATIONS {
+            assert!(layout(AREA, orientation, 0.5, 1, &[], MIN_WINDOW_SIZE).is_empty());
+        }
+    }
+
+    #[test]
+    fn more_front_windows_than_windows() {
+        let cells = layout(AREA, Orientation::Left, 0.5, 5, &[1.0; 3], MIN_WINDOW_SIZE);
+
+        assert_eq!(cells.len(), 3);
+        assert!(cells.iter().all(|cell| cell.width == AREA.width));
+        assert_eq!(
+            cells.iter().map(|cell| cell.height).sum::<usize>(),
+            AREA.height
+        );
+    }
+
+    #[test]
+    fn front_and_stack_fill_area() {
+        for orientation in ORIENTATIONS {
+            let cells = layout(AREA, orientation, 0.5, 1, &[1.0; 4], MIN_WINDOW_SIZE);
+
+            assert_eq!(cells.len(), 4);
+            assert!(cells.iter().all(|&cell| inside(AREA, cell)));
+
+            let covered = cells.iter().map(|c| c.width * c.height).sum::<usize>();
+            assert_eq!(covered, AREA.width * AREA.height);
+        }
+    }
+
+    #[test]
+    fn minimum_size_larger_than_area() {
+        let area = Rect {
+            x: 0,
+            y: 0,
+            width: 10,
+            height: 10,
+        };
+
+        for orientation in ORIENTATIONS {
+            let cells = layout(area, orientation, 0.5, 1, &[1.0; 3], 200);
+
+            assert_eq!(cells.len(), 3);
+            assert!(cells.iter().all(|&cell| inside(area, cell)));
+        }
+    }
+
+    #[test]
+    fn empty_area() {
+        let area = Rect {
+            x: 0,
+            y: 0,
+            width: 0,
+            height: 0,
+        };
+
+        for orientation in ORIENTATIONS {
+            let cells = layout(area, orientation, 0.5, 2, &[1.0; 5], MIN_WINDOW_SIZE);
+
+            assert_eq!(cells.len(), 5);
+            assert!(cells.iter().all(|cell| cell.width == 0 && cell.height == 0));
+        }
+    }
+
+    #[test]
+    fn windows_that_do_not_fit_share_last_cell() {
+        let area = Rect {
+            x: 0,
+            y: 0,
+            width: 500,
+            height: 100,
+        };
+
+        let cells = area.split_rows(&[1.0; 10], MIN_WINDOW_SIZE);
+
+        assert_eq!(fit(area.height, MIN_WINDOW_SIZE, 10), 3);
+        assert_eq!(cells.len(), 10);
+        assert!(cells[2..].iter().all(|&cell| cell == cells[2]));
+        assert_eq!(
+            cells[..3].iter().map(|c| c.height).sum::<usize>(),
+            area.height
+        );
+    }
+
+    #[test]
+    fn rows_follow_weights() {
+        let cells = AREA.split_rows(&[1.0, 3.0], MIN_WINDOW_SIZE);
+
+        assert_eq!(cells[0].height, AREA.height / 4);
+        assert_eq!(cells[1].height, AREA.height - AREA.height / 4);
+        assert_eq!(cells[1].y, AREA.y + cells[0].height);
     }
 }
@@ -0,0 +1,206 @@
+use crate::client::Clients;
+
+// Flags of WM_NORMAL_HINTS
+const P_MIN_SIZE: u32 = 1 << 4;
+const P_MAX_SIZE: u32 = 1 << 5;
+const P_RESIZE_INC: u32 = 1 << 6;
+const P_ASPECT: u32 = 1 << 7;
+const P_BASE_SIZE: u32 = 1 << 8;
+
+// Size constraints from WM_NORMAL_HINTS, sizes are zero when not given
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SizeHints {
+    pub min: (u32, u32),
+    pub max: (u32, u32),
+    pub base: (u32, u32),
+    pub increment: (u32, u32),
+    // Minimum and maximum width to height ratios
+    pub aspect: Option<(f32, f32)>,
+}
+
+impl SizeHints {
+    pub fn get(conn: &xcb_util::ewmh::Connection, window: xcb::Window) -> Option<Self> {
+        let reply = xcb::get_property(
+            conn,
+            false,
+            window,
+            xcb::ATOM_WM_NORMAL_HINTS,
+            xcb::ATOM_WM_SIZE_HINTS,
+            0,
+            18,
+        )
+        .get_reply()
+        .ok()?;
+
+        // The xcb-util accessors check the flags incorrectly, so read the property directly
+        let values = reply.value::<u32>();
+
+        if values.len() < 15 {
+            return None;
+        }
+
+        let flags = values[0];
+        let pair = |i: usize, flag: u32| {
+            if flags & flag != 0 {
+                (values[i], values[i + 1])
+            } else {
+                (0, 0)
+            }
+        };
+
+        let (min, max, increment) = (
+            pair(5, P_MIN_SIZE),
+            pair(7, P_MAX_SIZE),
+            pair(9, P_RESIZE_INC),
+        );
+
+        let base = if values.len() >= 17 {
+            pair(15, P_BASE_SIZE)
+        } else {
+            (0, 0)
+        };
+
+        let aspect = if flags & P_ASPECT != 0 && values[12] > 0 && values[14] > 0 {
+            Some((
+                values[11] as f32 / values[12] as f32,
+                values[13] as f32 / values[14] as f32,
+            ))
+        } else {
+            None
+        };
+
+        // Missing base and minimum sizes stand in for each other as ICCCM describes
+        Some(Self {
+            min: if flags & P_MIN_SIZE != 0 { min } else { base },
+            max,
+            base: if flags & P_BASE_SIZE != 0 { base } else { min },
+            increment,
+            aspect,
+        })
+    }
+
+    // A window that can not be resized
+    pub fn is_fixed(&self) -> bool {
+        self.max.0 > 0 && self.max.1 > 0 && self.min == self.max
+    }
+
+    // The largest size that fits in the given size and follows the hints
+    pub fn apply(&self, width: u32, height: u32) -> (u32, u32) {
+        let (mut width, mut height) = (width as f32, height as f32);
+        let (base_width, base_height) = (self.base.0 as f32, self.base.1 as f32);
+
+        // Aspect ratios apply to the size without the base size
+        if let Some((min_aspect, max_aspect)) = self.aspect {
+            let (w, h) = (width - base_width, height - base_height);
+
+            if h > 0.0 && max_aspect > 0.0 && w / h > max_aspect {
+                width = h * max_aspect + base_width;
+            } else if w > 0.0 && min_aspect > 0.0 && w / h < min_aspect {
+                height = w / min_aspect + base_height;
+            }
+        }
+
+        let (mut width, mut height) = (width as u32, height as u32);
+
+        if self.increment.0 > 0 && width > self.base.0 {
+            width -= (width - self.base.0) % self.increment.0;
+        }
+
+        if self.increment.1 > 0 && height > self.base.1 {
+            height -= (height - self.base.1) % self.increment.1;
+        }
+
+        width = width.max(self.min.0);
+        height = height.max(self.min.1);
+
+        if self.max.0 > 0 {
+            width = width.min(self.max.0);
+        }
+
+        if self.max.1 > 0 {
+            height = height.min(self.max.1);
+        }
+
+        (width.max(1), height.max(1))
+    }
+}
+
+impl Clients {
+    // Read WM_NORMAL_HINTS again, windows that can not be resized are not tiled
+    pub fn update_size_hints(&mut self, window: xcb::Window) {
+        let hints = SizeHints::get(&self.conn, window);
+
+        let client = match self.clients.iter_mut().find(|c| c.window == window) {
+            Some(client) => client,
+            None => return,
+        };
+
+        tracing::debug!("updating size hints; window={}; hints={:?}", window, hints);
+
+        client.size_hints = hints;
+
+        if client.controlled && matches!(hints, Some(hints) if hints.is_fixed()) {
+            self.set_controlled_status(window, false);
+        }
+
+        self.resize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SizeHints;
+
+    #[test]
+    fn without_hints_size_is_kept() {
+        assert_eq!(SizeHints::default().apply(800, 600), (800, 600));
+        assert_eq!(SizeHints::default().apply(0, 0), (1, 1));
+    }
+
+    #[test]
+    fn increments_from_base_size() {
+        let hints = SizeHints {
+            base: (4, 4),
+            increment: (10, 20),
+            ..SizeHints::default()
+        };
+
+        assert_eq!(hints.apply(1000, 500), (994, 484));
+        assert_eq!(hints.apply(2, 2), (2, 2));
+    }
+
+    #[test]
+    fn clamped_to_minimum_and_maximum() {
+        let hints = SizeHints {
+            min: (300, 200),
+            max: (400, 300),
+            ..SizeHints::default()
+        };
+
+        assert_eq!(hints.apply(100, 100), (300, 200));
+        assert_eq!(hints.apply(1000, 1000), (400, 300));
+    }
+
+    #[test]
+    fn keeps_aspect_ratio() {
+        let hints = SizeHints {
+            aspect: Some((1.0, 1.0)),
+            ..SizeHints::default()
+        };
+
+        assert_eq!(hints.apply(800, 400), (400, 400));
+        assert_eq!(hints.apply(400, 800), (400, 400));
+    }
+
+    #[test]
+    fn fixed_when_minimum_is_maximum() {
+        let hints = SizeHints {
+            min: (200, 100),
+            max: (200, 100),
+            ..SizeHints::default()
+        };
+
+        assert!(hints.is_fixed());
+        assert!(!SizeHints::default().is_fixed());
+    }
+}
@@ -0,0 +1,111 @@
+use crate::client::Clients;
+use crate::config::CommandLine;
+use crate::process;
+use crate::property::get_startup_id;
+use std::time::{Duration, Instant};
+
+// Launches without a window after this long are forgotten
+const LAUNCH_TIMEOUT: Duration = Duration::from_secs(60);
+
+// How far up the process tree a window's process is matched to a launched command
+const MAX_PARENT_DEPTH: usize = 8;
+
+// A command launched by the window manager, its windows are placed where it was launched
+pub struct Launch {
+    pub startup_id: String,
+    pub pid: Option<u32>,
+    pub workspace: u8,
+    pub started: Instant,
+}
+
+// Parent of a process from /proc, none for the init process or when unknown
+fn parent_pid(pid: u32) -> Option<u32> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+
+    // The command name in parentheses may contain spaces, the fields after it do not
+    let (_, fields) = stat.rsplit_once(')')?;
+    let ppid = fields.split_whitespace().nth(1)?.parse().ok()?;
+
+    Some(ppid).filter(|&ppid| ppid > 1)
+}
+
+impl Clients {
+    // Spawn a command with a startup ID so its windows open on the current workspace
+    pub fn launch(&mut self, command: &CommandLine, time: Option<xcb::Timestamp>) {
+        self.launch_count += 1;
+
+        // The time of the input that launched the command lets it take focus when it maps
+        let startup_id = match time.or(self.user_time) {
+            Some(time) => format!(
+                "mwm-{}-{}_TIME{}",
+                std::process::id(),
+                self.launch_count,
+                time
+            ),
+            None => format!("mwm-{}-{}", std::process::id(), self.launch_count),
+        };
+
+        let pid = process::spawn_detached(
+            command,
+            &[("DESKTOP_STARTUP_ID", startup_id.clone())],
+            self.config.command_log.as_deref(),
+        );
+
+        tracing::debug!(
+            "launched command; startup_id={}; pid={:?}; workspace={}",
+            startup_id,
+            pid,
+            self.active_workspace
+        );
+
+        self.launches
+            .retain(|l| l.started.elapsed() < LAUNCH_TIMEOUT);
+        self.launches.push(Launch {
+            startup_id,
+            pid,
+            workspace: self.active_workspace,
+            started: Instant::now(),
+        });
+    }
+
+    // Workspace a new window was launched from, by its startup ID or else by its process
+    pub fn launch_workspace(&mut self, window: xcb::Window) -> Option<u8> {
+        self.launches
+            .retain(|l| l.started.elapsed() < LAUNCH_TIMEOUT);
+
+        if self.launches.is_empty() {
+            return None;
+        }
+
+        // A launch is done once its window maps
+        if let Some(startup_id) = get_startup_id(&self.conn, window) {
+            if let Some(index) = self
+                .launches
+                .iter()
+                .position(|l| l.startup_id == startup_id)
+            {
+                let launch = self.launches.remove(index);
+                return Some(launch.workspace);
+            }
+        }
+
+        let mut pid = xcb_util::ewmh::get_wm_pid(&self.conn, window)
+            .get_reply()
+            .ok();
+
+        // Commands run through a shell or a launcher script are a parent of the window's process,
+        // only its first window is placed so windows opened later go where the user is
+        for _ in 0..MAX_PARENT_DEPTH {
+            let current = pid?;
+
+            if let Some(index) = self.launches.iter().position(|l| l.pid == Some(current)) {
+                let launch = self.launches.remove(index);
+                return Some(launch.workspace);
+            }
+
+            pid = parent_pid(current);
+        }
+
+        None
+    }
+}
@@ -0,0 +1,83 @@
+use crate::client::Clients;
+use crate::property::{intern_atom, set_wm_state, WM_STATE_ICONIC, WM_STATE_NORMAL};
+use crate::screen::get_screen;
+
+impl Clients {
+    pub fn minimize(&mut self, window: xcb::Window) {
+        let workspace = match self
+            .clients
+            .iter_mut()
+            .find(|c| c.window == window && !c.minimized)
+        {
+            Some(client) if Some(window) != self.dock_window => {
+                client.minimized = true;
+                client.workspace
+            }
+            _ => return,
+        };
+
+        tracing::debug!("minimizing client; window={}", window);
+
+        self.minimized.push(window);
+
+        set_wm_state(&self.conn, window, WM_STATE_ICONIC);
+        self.set_net_wm_state(window);
+        self.set_minimized_list();
+
+        self.refocus(window, workspace);
+        self.hide(window);
+    }
+
+    pub fn restore(&mut self, window: xcb::Window) {
+        let workspace = match self
+            .clients
+            .iter_mut()
+            .find(|c| c.window == window && c.minimized)
+        {
+            Some(client) => {
+                client.minimized = false;
+                client.workspace
+            }
+            None => return,
+        };
+
+        tracing::debug!("restoring client; window={}", window);
+
+        self.minimized.retain(|&w| w != window);
+
+        set_wm_state(&self.conn, window, WM_STATE_NORMAL);
+        self.set_net_wm_state(window);
+        self.set_minimized_list();
+
+        match workspace {
+            Some(workspace) if workspace != self.active_workspace => {
+                self.set_active_workspace(workspace);
+            }
+            _ => self.show(window),
+        }
+
+        self.set_active_window(Some(window));
+        self.warp_pointer(window);
+    }
+
+    pub fn restore_last(&mut self) {
+        if let Some(&window) = self.minimized.last() {
+            self.restore(window);
+        }
+    }
+
+    // Publish the minimized windows on the root window so they can be listed and restored
+    pub fn set_minimized_list(&self) {
+        let atom = intern_atom(&self.conn, "_MWM_MINIMIZED");
+
+        xcb::change_property(
+            &self.conn,
+            xcb::PROP_MODE_REPLACE as u8,
+            get_screen(&self.conn).root(),
+            atom,
+            xcb::ATOM_WINDOW,
+            32,
+            &self.minimized,
+        );
+    }
+}
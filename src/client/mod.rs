@@ -3,9 +3,23 @@ mod clients;
 
 mod action;
 mod create;
+mod direction;
+mod focus;
 mod geometry;
+mod hints;
+mod launch;
+mod minimize;
+mod rules;
+mod stack;
+mod state;
+mod transient;
+mod urgency;
+mod user_time;
 mod window;
 mod workspace;
 
 pub use client::Client;
-pub use clients::Clients;
+pub use clients::{Clients, FocusCycle, STICKY_DESKTOP};
+pub use hints::SizeHints;
+pub use launch::Launch;
+pub use rules::WindowBehavior;
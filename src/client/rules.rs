@@ -0,0 +1,163 @@
+use crate::client::Clients;
+use crate::config::WindowType;
+use crate::property::{get_wm_class, get_wm_title};
+
+// How a window is managed, from its type and the rules matching it
+#[derive(Clone, Copy, Debug)]
+pub struct WindowBehavior {
+    // Unmanaged windows are mapped as they are and otherwise left alone
+    pub managed: bool,
+    pub floating: bool,
+    pub focusable: bool,
+    pub border: bool,
+    pub center: bool,
+    pub below: bool,
+    // Shown on every workspace without belonging to one, like docks
+    pub global: bool,
+    pub workspace: Option<u8>,
+    pub steal_focus: Option<bool>,
+}
+
+impl WindowBehavior {
+    pub fn for_type(window_type: WindowType) -> Self {
+        let behavior = Self {
+            managed: true,
+            floating: false,
+            focusable: true,
+            border: true,
+            center: false,
+            below: false,
+            global: false,
+            workspace: None,
+            steal_focus: None,
+        };
+
+        match window_type {
+            WindowType::Normal => behavior,
+            WindowType::Dock => Self {
+                floating: true,
+                border: false,
+                global: true,
+                ..behavior
+            },
+            WindowType::Desktop => Self {
+                floating: true,
+                focusable: false,
+                border: false,
+                below: true,
+                global: true,
+                ..behavior
+            },
+            WindowType::Dialog | WindowType::Splash | WindowType::Utility => Self {
+                floating: true,
+                center: true,
+                ..behavior
+            },
+            WindowType::Toolbar | WindowType::Menu => Self {
+                floating: true,
+                ..behavior
+            },
+            WindowType::DropdownMenu
+            | WindowType::PopupMenu
+            | WindowType::Tooltip
+            | WindowType::Notification
+            | WindowType::Combo
+            | WindowType::Dnd => Self {
+                managed: false,
+                floating: true,
+                focusable: false,
+                border: false,
+                ..behavior
+            },
+        }
+    }
+}
+
+impl Clients {
+    // The first known type in _NET_WM_WINDOW_TYPE, windows without one are normal
+    pub fn window_type(&self, window: xcb::Window) -> WindowType {
+        let types = [
+            (self.conn.WM_WINDOW_TYPE_DESKTOP(), WindowType::Desktop),
+            (self.conn.WM_WINDOW_TYPE_DOCK(), WindowType::Dock),
+            (self.conn.WM_WINDOW_TYPE_TOOLBAR(), WindowType::Toolbar),
+            (self.conn.WM_WINDOW_TYPE_MENU(), WindowType::Menu),
+            (self.conn.WM_WINDOW_TYPE_UTILITY(), WindowType::Utility),
+            (self.conn.WM_WINDOW_TYPE_SPLASH(), WindowType::Splash),
+            (self.conn.WM_WINDOW_TYPE_DIALOG(), WindowType::Dialog),
+            (
+                self.conn.WM_WINDOW_TYPE_DROPDOWN_MENU(),
+                WindowType::DropdownMenu,
+            ),
+            (self.conn.WM_WINDOW_TYPE_POPUP_MENU(), WindowType::PopupMenu),
+            (self.conn.WM_WINDOW_TYPE_TOOLTIP(), WindowType::Tooltip),
+            (
+                self.conn.WM_WINDOW_TYPE_NOTIFICATION(),
+                WindowType::Notification,
+            ),
+            (self.conn.WM_WINDOW_TYPE_COMBO(), WindowType::Combo),
+            (self.conn.WM_WINDOW_TYPE_DND(), WindowType::Dnd),
+            (self.conn.WM_WINDOW_TYPE_NORMAL(), WindowType::Normal),
+        ];
+
+        xcb_util::ewmh::get_wm_window_type(&self.conn, window)
+            .get_reply()
+            .ok()
+            .and_then(|reply| {
+                reply.atoms().iter().find_map(|atom| {
+                    types
+                        .iter()
+                        .find(|(type_atom, _)| type_atom == atom)
+                        .map(|(_, window_type)| *window_type)
+                })
+            })
+            .unwrap_or_default()
+    }
+
+    // Apply the rules matching a window in order, later rules override earlier ones
+    pub fn apply_rules(
+        &self,
+        window: xcb::Window,
+        window_type: WindowType,
+        behavior: &mut WindowBehavior,
+    ) {
+        if self.config.rules.is_empty() {
+            return;
+        }
+
+        let (instance, class) = get_wm_class(&self.conn, window).unwrap_or_default();
+        let title = get_wm_title(&self.conn, window).unwrap_or_default();
+
+        for rule in self.config.rules.iter() {
+            let matches = rule.class.as_ref().map(|c| *c == class).unwrap_or(true)
+                && rule
+                    .instance
+                    .as_ref()
+                    .map(|i| *i == instance)
+                    .unwrap_or(true)
+                && rule
+                    .title
+                    .as_ref()
+                    .map(|t| title.contains(t))
+                    .unwrap_or(true)
+                && rule.window_type.map(|t| t == window_type).unwrap_or(true);
+
+            if !matches {
+                continue;
+            }
+
+            tracing::debug!("applying rule; window={}; class={}", window, class);
+
+            behavior.managed = rule.managed.unwrap_or(behavior.managed);
+            behavior.floating = rule.floating.unwrap_or(behavior.floating);
+            behavior.focusable = rule.focusable.unwrap_or(behavior.focusable);
+            behavior.border = rule.border.unwrap_or(behavior.border);
+            behavior.center = rule.center.unwrap_or(behavior.center);
+            behavior.below = rule.below.unwrap_or(behavior.below);
+            behavior.workspace = rule
+                .workspace
+                .filter(|workspace| (1..=9).contains(workspace))
+                .or(behavior.workspace);
+            behavior.steal_focus = rule.steal_focus.or(behavior.steal_focus);
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use crate::client::{Client, Clients};
+
+impl Clients {
+    // Reorder the tiled windows, the focused window keeps focus
+    fn reorder_stack<F>(&mut self, reorder: F)
+    where
+        F: FnOnce(&mut Vec<Client>, usize),
+    {
+        let window = match self.active_window() {
+            Some(window) => window,
+            None => return,
+        };
+
+        let positions = self
+            .clients
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.visible && c.controlled)
+            .map(|(i, _)| i)
+            .collect::<Vec<usize>>();
+
+        let mut tiled = positions
+            .iter()
+            .map(|&i| self.clients[i].clone())
+            .collect::<Vec<Client>>();
+
+        let index = match tiled.iter().position(|c| c.window == window) {
+            Some(index) => index,
+            None => return,
+        };
+
+        if tiled.len() < 2 {
+            return;
+        }
+
+        reorder(&mut tiled, index);
+
+        for (&i, client) in positions.iter().zip(tiled) {
+            self.clients[i] = client;
+        }
+
+        self.resize();
+        self.warp_pointer(window);
+    }
+
+    // Swap the focused window into the master slot, or out of it when already there
+    pub fn zoom(&mut self) {
+        tracing::debug!("zooming focused window");
+
+        self.reorder_stack(|tiled, index| {
+            if index == 0 {
+                tiled.swap(0, 1);
+            } else {
+                let client = tiled.remove(index);
+                tiled.insert(0, client);
+            }
+        });
+    }
+
+    pub fn move_up(&mut self) {
+        tracing::debug!("moving focused window up");
+
+        self.reorder_stack(|tiled, index| {
+            if index == 0 {
+                tiled.rotate_left(1);
+            } else {
+                tiled.swap(index, index - 1);
+            }
+        });
+    }
+
+    pub fn move_down(&mut self) {
+        tracing::debug!("moving focused window down");
+
+        self.reorder_stack(|tiled, index| {
+            if index == tiled.len() - 1 {
+                tiled.rotate_right(1);
+            } else {
+                tiled.swap(index, index + 1);
+            }
+        });
+    }
+
+    // Clockwise moves each window one slot down the stack and the last window into the master slot
+    pub fn rotate_stack(&mut self, clockwise: bool) {
+        tracing::debug!("rotating stack; clockwise={}", clockwise);
+
+        self.reorder_stack(|tiled, _| {
+            if clockwise {
+                tiled.rotate_right(1);
+            } else {
+                tiled.rotate_left(1);
+            }
+        });
+    }
+}
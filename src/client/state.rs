@@ -0,0 +1,196 @@
+use crate::client::Clients;
+use crate::screen::get_screen;
+
+impl Clients {
+    // Apply a _NET_WM_STATE change requested by a client or pager
+    pub fn change_state(&mut self, window: xcb::Window, action: u32, atom: xcb::Atom) {
+        if atom == xcb::ATOM_NONE {
+            return;
+        }
+
+        let (status, toggle) = match action {
+            xcb_util::ewmh::STATE_ADD => (Some(true), false),
+            xcb_util::ewmh::STATE_TOGGLE => (None, true),
+            _ => (None, false),
+        };
+
+        tracing::debug!(
+            "change window state; window={}; action={}; atom={}",
+            window,
+            action,
+            atom
+        );
+
+        if atom == self.conn.WM_STATE_FULLSCREEN() {
+            self.set_full_screen(window, status, toggle);
+        } else if atom == self.conn.WM_STATE_STICKY() {
+            self.set_sticky(window, status, toggle);
+        } else if atom == self.conn.WM_STATE_HIDDEN() {
+            let minimized = self.minimized.contains(&window);
+
+            if Some(true) == status || (!minimized && toggle) {
+                self.minimize(window);
+            } else {
+                self.restore(window);
+            }
+        } else if self.state_atoms().contains(&atom) {
+            self.set_state(window, atom, status, toggle);
+        }
+    }
+
+    // States kept as given and written back, the window manager acts on some of them
+    fn state_atoms(&self) -> [xcb::Atom; 7] {
+        [
+            self.conn.WM_STATE_MAXIMIZED_VERT(),
+            self.conn.WM_STATE_MAXIMIZED_HORZ(),
+            self.conn.WM_STATE_ABOVE(),
+            self.conn.WM_STATE_BELOW(),
+            self.conn.WM_STATE_SKIP_TASKBAR(),
+            self.conn.WM_STATE_SKIP_PAGER(),
+            self.conn.WM_STATE_DEMANDS_ATTENTION(),
+        ]
+    }
+
+    // Keep the states a client set before it was managed
+    pub fn load_states(&mut self, window: xcb::Window) -> Vec<xcb::Atom> {
+        let atoms = xcb_util::ewmh::get_wm_state(&self.conn, window)
+            .get_reply()
+            .map(|reply| reply.atoms().to_vec())
+            .unwrap_or_default();
+
+        let state_atoms = self.state_atoms();
+
+        atoms
+            .into_iter()
+            .filter(|atom| state_atoms.contains(atom))
+            .collect()
+    }
+
+    pub fn has_state(&self, window: xcb::Window, atom: xcb::Atom) -> bool {
+        self.clients
+            .iter()
+            .any(|c| c.window == window && c.states.contains(&atom))
+    }
+
+    pub fn set_state(
+        &mut self,
+        window: xcb::Window,
+        atom: xcb::Atom,
+        status: Option<bool>,
+        toggle: bool,
+    ) {
+        let client = match self.clients.iter_mut().find(|c| c.window == window) {
+            Some(client) => client,
+            None => return,
+        };
+
+        let enabled = Some(true) == status || (!client.states.contains(&atom) && toggle);
+
+        client.states.retain(|&a| a != atom);
+
+        if enabled {
+            client.states.push(atom);
+
+            // Above and below exclude each other
+            let opposite = if atom == self.conn.WM_STATE_ABOVE() {
+                self.conn.WM_STATE_BELOW()
+            } else if atom == self.conn.WM_STATE_BELOW() {
+                self.conn.WM_STATE_ABOVE()
+            } else {
+                xcb::ATOM_NONE
+            };
+
+            client.states.retain(|&a| a != opposite);
+        }
+
+        self.set_net_wm_state(window);
+
+        if atom == self.conn.WM_STATE_DEMANDS_ATTENTION() {
+            self.update_urgency(window);
+        }
+
+        if atom == self.conn.WM_STATE_ABOVE() || atom == self.conn.WM_STATE_BELOW() {
+            self.restack(window);
+        }
+
+        if atom == self.conn.WM_STATE_MAXIMIZED_VERT()
+            || atom == self.conn.WM_STATE_MAXIMIZED_HORZ()
+        {
+            self.maximize(window);
+        }
+    }
+
+    // Keep windows marked above or below other windows in place
+    pub fn restack(&self, window: xcb::Window) {
+        let stack_mode = if self.has_state(window, self.conn.WM_STATE_ABOVE()) {
+            xcb::STACK_MODE_ABOVE
+        } else if self.has_state(window, self.conn.WM_STATE_BELOW()) {
+            xcb::STACK_MODE_BELOW
+        } else {
+            return;
+        };
+
+        xcb::configure_window(
+            &self.conn,
+            window,
+            &[(xcb::CONFIG_WINDOW_STACK_MODE as u16, stack_mode)],
+        );
+    }
+
+    // Maximize floating windows, tiled windows already fill their space
+    pub fn maximize(&mut self, window: xcb::Window) {
+        let vertical = self.has_state(window, self.conn.WM_STATE_MAXIMIZED_VERT());
+        let horizontal = self.has_state(window, self.conn.WM_STATE_MAXIMIZED_HORZ());
+
+        let screen = get_screen(&self.conn);
+        let padding_top = self.get_padding_top() as u32;
+        let border = self.config.border_thickness;
+
+        let geometry = match xcb::get_geometry(&self.conn, window).get_reply() {
+            Ok(geometry) => geometry,
+            Err(_) => return,
+        };
+
+        let client = match self.clients.iter_mut().find(|c| c.window == window) {
+            Some(client) if !client.controlled => client,
+            _ => return,
+        };
+
+        // Remember where the window was to put it back when no longer maximized
+        if client.restore_geometry.is_none() {
+            client.restore_geometry = Some([
+                geometry.x() as u32,
+                geometry.y() as u32,
+                geometry.width() as u32,
+                geometry.height() as u32,
+            ]);
+        }
+
+        let [mut x, mut y, mut width, mut height] = client.restore_geometry.unwrap();
+
+        if horizontal {
+            x = 0;
+            width = (screen.width_in_pixels() as u32).saturating_sub(border * 2);
+        }
+
+        if vertical {
+            y = padding_top;
+            height = (screen.height_in_pixels() as u32).saturating_sub(padding_top + border * 2);
+        }
+
+        if !vertical && !horizontal {
+            client.restore_geometry = None;
+        }
+
+        xcb::configure_window(
+            &self.conn,
+            window,
+            &[
+                (xcb::CONFIG_WINDOW_X as u16, x),
+                (xcb::CONFIG_WINDOW_Y as u16, y),
+                (xcb::CONFIG_WINDOW_WIDTH as u16, width.max(1)),
+                (xcb::CONFIG_WINDOW_HEIGHT as u16, height.max(1)),
+            ],
+        );
+    }
+}
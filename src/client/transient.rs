@@ -0,0 +1,94 @@
+use crate::client::Clients;
+use crate::screen::get_screen;
+
+impl Clients {
+    // Windows that are transient for a window, such as its dialogs
+    pub fn transients(&self, window: xcb::Window) -> Vec<xcb::Window> {
+        self.clients
+            .iter()
+            .filter(|c| c.transient_for == Some(window))
+            .map(|c| c.window)
+            .collect()
+    }
+
+    // Position centring a window of the given size on its parent, or the screen without one
+    pub fn center_position(&self, window: xcb::Window, width: u16, height: u16) -> (i16, i16) {
+        let parent = self
+            .clients
+            .iter()
+            .find(|c| c.window == window)
+            .and_then(|c| c.transient_for);
+
+        let parent_geometry =
+            parent.and_then(|parent| xcb::get_geometry(&self.conn, parent).get_reply().ok());
+
+        let (x, y, area_width, area_height) = match parent_geometry {
+            Some(geometry) => (
+                geometry.x() as i32,
+                geometry.y() as i32,
+                geometry.width() as i32,
+                geometry.height() as i32,
+            ),
+            None => {
+                let screen = get_screen(&self.conn);
+                (
+                    0,
+                    0,
+                    screen.width_in_pixels() as i32,
+                    screen.height_in_pixels() as i32,
+                )
+            }
+        };
+
+        (
+            (x + (area_width - width as i32) / 2).max(0) as i16,
+            (y + (area_height - height as i32) / 2).max(0) as i16,
+        )
+    }
+
+    pub fn center_window(&self, window: xcb::Window) {
+        if let Ok(geometry) = xcb::get_geometry(&self.conn, window).get_reply() {
+            let (x, y) = self.center_position(window, geometry.width(), geometry.height());
+
+            xcb::configure_window(
+                &self.conn,
+                window,
+                &[
+                    (xcb::CONFIG_WINDOW_X as u16, x as u32),
+                    (xcb::CONFIG_WINDOW_Y as u16, y as u32),
+                ],
+            );
+        }
+    }
+
+    // Keep transient windows above the windows they belong to
+    pub fn raise_transients(&self) {
+        for client in self
+            .clients
+            .iter()
+            .filter(|c| c.visible && c.transient_for.is_some())
+        {
+            xcb::configure_window(
+                &self.conn,
+                client.window,
+                &[(xcb::CONFIG_WINDOW_STACK_MODE as u16, xcb::STACK_MODE_ABOVE)],
+            );
+        }
+    }
+
+    // Close the transient windows of a destroyed window, they are of no use without it
+    pub fn close_transients(&mut self, window: xcb::Window) {
+        for transient in self.transients(window) {
+            tracing::debug!(
+                "closing transient window; window={}; parent={}",
+                transient,
+                window
+            );
+
+            // Killing the client would take the whole application with it
+            self.send_delete_window(transient);
+        }
+
+        self.conn.flush();
+    }
+}
@@ -0,0 +1,101 @@
+use crate::client::Clients;
+use crate::property::{get_wm_urgency, intern_atom};
+use crate::screen::get_screen;
+
+impl Clients {
+    // Urgent when the urgency hint is set or the window demands attention, except when focused
+    pub fn update_urgency(&mut self, window: xcb::Window) {
+        let demands_attention = self.has_state(window, self.conn.WM_STATE_DEMANDS_ATTENTION());
+        let urgent = (get_wm_urgency(&self.conn, window) || demands_attention)
+            && self.active_window() != Some(window);
+
+        self.set_urgent(window, urgent);
+    }
+
+    pub fn set_urgent(&mut self, window: xcb::Window, urgent: bool) {
+        let client = match self.clients.iter_mut().find(|c| c.window == window) {
+            Some(client) => client,
+            None => return,
+        };
+
+        if client.urgent == urgent {
+            return;
+        }
+
+        tracing::debug!("set urgent status; window={}; urgent={}", window, urgent);
+
+        client.urgent = urgent;
+
+        self.urgent.retain(|&w| w != window);
+
+        let border = if urgent {
+            self.urgent.push(window);
+            self.config.urgent_border
+        } else if self.active_window() == Some(window) {
+            self.config.active_border
+        } else {
+            self.config.inactive_border
+        };
+
+        xcb::change_window_attributes(&self.conn, window, &[(xcb::CW_BORDER_PIXEL, border)]);
+
+        // Clear the state directly, going through set_state would check the urgency again
+        if !urgent && self.has_state(window, self.conn.WM_STATE_DEMANDS_ATTENTION()) {
+            let demands_attention = self.conn.WM_STATE_DEMANDS_ATTENTION();
+
+            if let Some(client) = self.clients.iter_mut().find(|c| c.window == window) {
+                client.states.retain(|&atom| atom != demands_attention);
+            }
+
+            self.set_net_wm_state(window);
+        }
+
+        self.set_workspace_names();
+        self.set_urgent_workspaces();
+    }
+
+    // Jump to the window that most recently became urgent
+    pub fn focus_urgent(&mut self) {
+        if let Some(&window) = self.urgent.last() {
+            tracing::debug!("focusing urgent window; window={}", window);
+
+            if self.minimized.contains(&window) {
+                self.restore(window);
+            } else {
+                self.focus_window(window);
+            }
+        }
+    }
+
+    pub fn urgent_workspaces(&self) -> Vec<u8> {
+        let mut workspaces = self
+            .clients
+            .iter()
+            .filter(|c| c.urgent)
+            .filter_map(|c| c.workspace)
+            .collect::<Vec<u8>>();
+
+        workspaces.sort_unstable();
+        workspaces.dedup();
+        workspaces
+    }
+
+    // Publish the workspaces with urgent windows on the root window for statusbars
+    fn set_urgent_workspaces(&self) {
+        let workspaces = self
+            .urgent_workspaces()
+            .into_iter()
+            .map(u32::from)
+            .collect::<Vec<u32>>();
+
+        xcb::change_property(
+            &self.conn,
+            xcb::PROP_MODE_REPLACE as u8,
+            get_screen(&self.conn).root(),
+            intern_atom(&self.conn, "_MWM_URGENT"),
+            xcb::ATOM_CARDINAL,
+            32,
+            &workspaces,
+        );
+    }
+}
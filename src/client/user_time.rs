@@ -0,0 +1,90 @@
+use crate::client::Clients;
+use crate::config::FocusStealing;
+use crate::property::{get_startup_time, get_wm_user_time};
+
+// Whether timestamp a is before b, X timestamps wrap around
+fn is_before(a: xcb::Timestamp, b: xcb::Timestamp) -> bool {
+    (a.wrapping_sub(b) as i32) < 0
+}
+
+impl Clients {
+    // Remember the time of the latest input seen by the window manager
+    pub fn set_user_time(&mut self, time: xcb::Timestamp) {
+        if time == xcb::CURRENT_TIME {
+            return;
+        }
+
+        match self.user_time {
+            Some(user_time) if !is_before(user_time, time) => {}
+            _ => self.user_time = Some(time),
+        }
+    }
+
+    // Latest input to the window manager or the focused window
+    fn last_input_time(&mut self) -> Option<xcb::Timestamp> {
+        let focused_time = self
+            .active_window()
+            .and_then(|window| get_wm_user_time(&self.conn, window));
+
+        match (self.user_time, focused_time) {
+            (Some(a), Some(b)) if is_before(a, b) => Some(b),
+            (Some(a), _) => Some(a),
+            (None, b) => b,
+        }
+    }
+
+    // Whether a new window may take focus from the focused window
+    pub fn allow_focus(
+        &mut self,
+        window: xcb::Window,
+        transient_for: Option<xcb::Window>,
+        steal_focus: Option<bool>,
+    ) -> bool {
+        if let Some(steal_focus) = steal_focus {
+            return steal_focus;
+        }
+
+        let policy = self.config.focus_stealing;
+
+        if policy == FocusStealing::Allow {
+            return true;
+        }
+
+        let active_window = self.active_window();
+
+        // Nothing to take focus from, or a dialog of the focused window
+        if active_window.is_none() || (transient_for.is_some() && transient_for == active_window) {
+            return true;
+        }
+
+        // Windows already shown before they were managed, such as when restarting in place
+        let viewable = xcb::get_window_attributes(&self.conn, window)
+            .get_reply()
+            .map(|attributes| attributes.map_state() == xcb::MAP_STATE_VIEWABLE as u8)
+            .unwrap_or(false);
+
+        if viewable {
+            return true;
+        }
+
+        let window_time =
+            get_wm_user_time(&self.conn, window).or_else(|| get_startup_time(&self.conn, window));
+
+        let allowed = match (window_time, self.last_input_time()) {
+            // A user time of zero asks not to be focused when mapped
+            (Some(0), _) => false,
+            (Some(window_time), Some(input_time)) => !is_before(window_time, input_time),
+            (Some(_), None) => true,
+            (None, _) => policy == FocusStealing::Smart,
+        };
+
+        tracing::debug!(
+            "focus stealing check; window={}; window_time={:?}; allowed={}",
+            window,
+            window_time,
+            allowed
+        );
+
+        allowed
+    }
+}
@@ -1,21 +1,49 @@
-use crate::client::Clients;
+use crate::client::{Client, Clients, STICKY_DESKTOP};
+use crate::config::FocusModel;
+use crate::event::{Event, FocusChangedEvent, FullScreenEvent, WindowDestroyedEvent};
+use crate::property::{set_wm_state, WM_STATE_WITHDRAWN};
 
 impl Clients {
     pub fn destroy(&mut self, window: xcb::Window) {
         tracing::debug!("destroying client; window={}", window);
 
+        let workspace = match self.clients.iter().find(|c| c.window == window) {
+            Some(client) => client.workspace,
+            None => return,
+        };
+
+        self.close_transients(window);
+        self.unmanage(window, workspace);
+
+        self.emit(Event::WindowDestroyed(WindowDestroyedEvent {
+            window,
+            workspace,
+        }));
+    }
+
+    // Stop managing a window, leaving the window itself alone
+    fn unmanage(&mut self, window: xcb::Window, workspace: Option<u8>) {
         self.clients.retain(|c| c.window != window);
+        self.pending_unmaps.remove(&window);
+        self.focus_history.retain(|&w| w != window);
+        self.urgent.retain(|&w| w != window);
+
+        if let Some(cycle) = self.focus_cycle.as_mut() {
+            cycle.windows.retain(|&w| w != window);
+            cycle.index = cycle.index.min(cycle.windows.len().saturating_sub(1));
+        }
+
+        if self.minimized.contains(&window) {
+            self.minimized.retain(|&w| w != window);
+            self.set_minimized_list();
+        }
 
-        if self.active_window() == Some(window) {
-            let active_window = self
-                .clients
-                .iter()
-                .filter(|c| c.controlled)
-                .next()
-                .map_or(None, |c| Some(c.window));
-            self.set_active_window(active_window);
+        if self.dock_window == Some(window) {
+            self.dock_window = None;
         }
 
+        self.refocus(window, workspace);
+
         self.refresh_clients();
     }
 
@@ -26,6 +54,7 @@ impl Clients {
             if window == client.window {
                 if client.visible {
                     xcb::unmap_window(&self.conn, client.window);
+                    *self.pending_unmaps.entry(window).or_insert(0) += 1;
                 }
 
                 client.visible = false;
@@ -39,6 +68,31 @@ impl Clients {
         self.resize();
     }
 
+    // A window unmapping itself is withdrawn as required by ICCCM and no longer managed until
+    // it is mapped again, unmaps by the window manager only hide it
+    pub fn unmap_notify(&mut self, window: xcb::Window) {
+        if let Some(count) = self.pending_unmaps.get_mut(&window) {
+            if *count > 0 {
+                *count -= 1;
+                return;
+            }
+        }
+
+        let workspace = match self.clients.iter().find(|c| c.window == window) {
+            Some(client) => client.workspace,
+            None => return,
+        };
+
+        tracing::debug!("window withdrawn; window={}", window);
+
+        set_wm_state(&self.conn, window, WM_STATE_WITHDRAWN);
+        xcb::delete_property(&self.conn, window, self.conn.WM_DESKTOP());
+        xcb::delete_property(&self.conn, window, self.conn.WM_STATE());
+
+        self.unmanage(window, workspace);
+        self.resize();
+    }
+
     pub fn show(&mut self, window: xcb::Window) {
         tracing::debug!("showing client; window={}", window);
 
@@ -69,7 +123,9 @@ impl Clients {
                 xcb::CW_EVENT_MASK,
                 xcb::EVENT_MASK_PROPERTY_CHANGE
                     | xcb::EVENT_MASK_STRUCTURE_NOTIFY
-                    | xcb::EVENT_MASK_ENTER_WINDOW,
+                    | xcb::EVENT_MASK_ENTER_WINDOW
+                    | xcb::EVENT_MASK_LEAVE_WINDOW
+                    | xcb::EVENT_MASK_FOCUS_CHANGE,
             )],
         );
     }
@@ -82,7 +138,9 @@ impl Clients {
             window,
             &[(
                 xcb::CW_EVENT_MASK,
-                xcb::EVENT_MASK_PROPERTY_CHANGE | xcb::EVENT_MASK_STRUCTURE_NOTIFY,
+                xcb::EVENT_MASK_PROPERTY_CHANGE
+                    | xcb::EVENT_MASK_STRUCTURE_NOTIFY
+                    | xcb::EVENT_MASK_FOCUS_CHANGE,
             )],
         );
     }
@@ -99,7 +157,19 @@ impl Clients {
     }
 
     pub fn set_active_window(&mut self, window: Option<xcb::Window>) {
-        if window == self.dock_window {
+        if window.is_some() && window == self.dock_window {
+            return;
+        }
+
+        // Windows such as desktops never take focus
+        let focusable = self
+            .clients
+            .iter()
+            .find(|c| Some(c.window) == window)
+            .map(|c| c.focusable)
+            .unwrap_or(true);
+
+        if !focusable {
             return;
         }
 
@@ -108,6 +178,15 @@ impl Clients {
 
         if let Some(window) = window {
             tracing::debug!("set active status; window={:?}", window);
+
+            // Focusing a window answers its request for attention
+            self.set_urgent(window, false);
+
+            // While cycling the history is left alone until a window is picked
+            if self.focus_cycle.is_none() {
+                self.push_focus_history(window);
+            }
+
             xcb::set_input_focus(
                 &self.conn,
                 xcb::INPUT_FOCUS_PARENT as u8,
@@ -119,29 +198,115 @@ impl Clients {
                 window,
                 &[(xcb::CW_BORDER_PIXEL, active_border)],
             );
+            self.raise_overlapping(window);
+            self.raise_transients();
+        } else {
+            xcb::set_input_focus(
+                &self.conn,
+                xcb::INPUT_FOCUS_POINTER_ROOT as u8,
+                xcb::INPUT_FOCUS_POINTER_ROOT,
+                xcb::CURRENT_TIME,
+            );
         }
 
         xcb_util::ewmh::set_active_window(&self.conn, 0, window.unwrap_or(xcb::WINDOW_NONE));
 
-        if window != self.active_window() {
+        let previous = self.active_window();
+
+        if window != previous {
             tracing::debug!(
                 "set previous active window to inactive; previous_window={:?}",
                 self.active_window
             );
-            if let Some(active_window) = self.active_window() {
+            if let Some(active_window) = previous {
+                let border = if self.urgent.contains(&active_window) {
+                    self.config.urgent_border
+                } else {
+                    inactive_border
+                };
+
                 xcb::change_window_attributes(
                     &self.conn,
                     active_window,
-                    &[(xcb::CW_BORDER_PIXEL, inactive_border)],
+                    &[(xcb::CW_BORDER_PIXEL, border)],
                 );
+
+                if self.clients.iter().any(|c| c.window == active_window) {
+                    self.grab_buttons(active_window);
+                }
+            }
+
+            if let Some(window) = window {
+                self.ungrab_buttons(window);
             }
 
             self.active_window.insert(self.active_workspace, window);
+
+            self.emit(Event::FocusChanged(FocusChangedEvent {
+                window,
+                previous,
+                workspace: self.active_workspace,
+            }));
         }
 
         self.conn.flush();
     }
 
+    // With click to focus, clicks on unfocused windows are intercepted to focus them
+    pub fn grab_buttons(&self, window: xcb::Window) {
+        if self.config.focus_model != FocusModel::Click {
+            return;
+        }
+
+        xcb::grab_button(
+            &self.conn,
+            false,
+            window,
+            xcb::EVENT_MASK_BUTTON_PRESS as u16,
+            xcb::GRAB_MODE_SYNC as u8,
+            xcb::GRAB_MODE_ASYNC as u8,
+            xcb::NONE,
+            xcb::NONE,
+            xcb::BUTTON_INDEX_ANY as u8,
+            xcb::MOD_MASK_ANY as u16,
+        );
+    }
+
+    pub fn ungrab_buttons(&self, window: xcb::Window) {
+        if self.config.focus_model != FocusModel::Click {
+            return;
+        }
+
+        xcb::ungrab_button(
+            &self.conn,
+            xcb::BUTTON_INDEX_ANY as u8,
+            window,
+            xcb::MOD_MASK_ANY as u16,
+        );
+    }
+
+    pub fn warp_pointer(&self, window: xcb::Window) {
+        if !self.config.warp_pointer {
+            return;
+        }
+
+        if let Ok(geometry) = xcb::get_geometry(&self.conn, window).get_reply() {
+            tracing::debug!("warping pointer; window={}", window);
+
+            xcb::warp_pointer(
+                &self.conn,
+                xcb::NONE,
+                window,
+                0,
+                0,
+                0,
+                0,
+                (geometry.width() / 2) as i16,
+                (geometry.height() / 2) as i16,
+            );
+        }
+    }
+
     pub fn active_window(&mut self) -> Option<u32> {
         self.active_window
             .entry(self.active_workspace)
@@ -149,28 +314,162 @@ impl Clients {
             .to_owned()
     }
 
+    // Set _NET_WM_STATE from the state of the client
+    pub fn set_net_wm_state(&self, window: xcb::Window) {
+        let client = match self.clients.iter().find(|c| c.window == window) {
+            Some(client) => client,
+            None => return,
+        };
+
+        let mut atoms = Vec::new();
+
+        if client.full_screen {
+            atoms.push(self.conn.WM_STATE_FULLSCREEN());
+        }
+
+        if client.minimized {
+            atoms.push(self.conn.WM_STATE_HIDDEN());
+        }
+
+        if client.sticky {
+            atoms.push(self.conn.WM_STATE_STICKY());
+        }
+
+        atoms.extend(&client.states);
+
+        xcb::change_property(
+            &self.conn,
+            xcb::PROP_MODE_REPLACE as u8,
+            window,
+            self.conn.WM_STATE(),
+            xcb::ATOM_ATOM,
+            32,
+            &atoms,
+        );
+    }
+
+    // Set _NET_WM_DESKTOP from the workspace of the client
+    pub fn set_wm_desktop(&self, window: xcb::Window) {
+        let desktop = match self.clients.iter().find(|c| c.window == window) {
+            Some(client) if client.sticky => STICKY_DESKTOP,
+            Some(Client {
+                workspace: Some(workspace),
+                ..
+            }) => *workspace as u32,
+            _ => return,
+        };
+
+        xcb_util::ewmh::set_wm_desktop(&self.conn, window, desktop);
+    }
+
+    pub fn set_sticky(&mut self, window: xcb::Window, status: Option<bool>, toggle: bool) {
+        let sticky = match self.clients.iter_mut().find(|c| c.window == window) {
+            Some(client) => {
+                client.sticky = Some(true) == status || (!client.sticky && toggle);
+                client.sticky
+            }
+            None => return,
+        };
+
+        tracing::debug!("set sticky status; window={}; sticky={}", window, sticky);
+
+        self.set_wm_desktop(window);
+        self.set_net_wm_state(window);
+
+        for transient in self.transients(window) {
+            self.set_sticky(transient, Some(sticky), false);
+        }
+
+        // A window no longer sticky belongs to the workspace it is seen on
+        if !sticky {
+            let active_workspace = self.active_workspace;
+
+            if let Some(client) = self.clients.iter_mut().find(|c| c.window == window) {
+                if client.visible && client.workspace.is_some() {
+                    client.workspace = Some(active_workspace);
+                }
+            }
+
+            self.set_wm_desktop(window);
+            self.set_workspace_names();
+        }
+    }
+
+    // Save the geometry of a floating window going full screen, or put it back when leaving
+    fn restore_floating_geometry(&mut self, window: xcb::Window, full_screen: bool) {
+        let border = self.config.border_thickness;
+        let geometry = xcb::get_geometry(&self.conn, window).get_reply();
+
+        let client = match self.clients.iter_mut().find(|c| c.window == window) {
+            Some(client) => client,
+            None => return,
+        };
+
+        if full_screen {
+            if let (None, Ok(geometry)) = (client.restore_geometry, geometry) {
+                client.restore_geometry = Some([
+                    geometry.x() as u32,
+                    geometry.y() as u32,
+                    geometry.width() as u32,
+                    geometry.height() as u32,
+                ]);
+            }
+
+            return;
+        }
+
+        if let Some([x, y, width, height]) = client.restore_geometry.take() {
+            xcb::configure_window(
+                &self.conn,
+                window,
+                &[
+                    (xcb::CONFIG_WINDOW_X as u16, x),
+                    (xcb::CONFIG_WINDOW_Y as u16, y),
+                    (xcb::CONFIG_WINDOW_WIDTH as u16, width),
+                    (xcb::CONFIG_WINDOW_HEIGHT as u16, height),
+                    (xcb::CONFIG_WINDOW_BORDER_WIDTH as u16, border),
+                ],
+            );
+        }
+
+        // A maximized window stays maximized
+        if self.has_state(window, self.conn.WM_STATE_MAXIMIZED_VERT())
+            || self.has_state(window, self.conn.WM_STATE_MAXIMIZED_HORZ())
+        {
+            self.maximize(window);
+        }
+    }
+
     pub fn set_full_screen(&mut self, window: xcb::Window, status: Option<bool>, toggle: bool) {
         for mut client in self.clients.iter_mut() {
             if window == client.window {
-                client.full_screen = Some(true) == status || (!client.full_screen && toggle);
+                let full_screen = Some(true) == status || (!client.full_screen && toggle);
+                let changed = full_screen != client.full_screen;
+                client.full_screen = full_screen;
+                let workspace = client.workspace;
+                let controlled = client.controlled;
+                let visible = client.visible;
 
-                let data = if client.full_screen {
-                    self.conn.WM_STATE_FULLSCREEN()
-                } else {
-                    0
-                };
+                self.set_net_wm_state(window);
 
-                xcb::change_property(
-                    &self.conn,
-                    xcb::PROP_MODE_REPLACE as u8,
-                    window,
-                    self.conn.WM_STATE(),
-                    xcb::ATOM_ATOM,
-                    32,
-                    &[data],
-                );
+                if changed && !controlled {
+                    self.restore_floating_geometry(window, full_screen);
+                }
 
                 self.resize();
+
+                if full_screen && visible {
+                    self.set_active_window(Some(window));
+                }
+
+                if changed {
+                    self.emit(Event::FullScreen(FullScreenEvent {
+                        window,
+                        workspace,
+                        full_screen,
+                    }));
+                }
+
                 break;
             }
         }
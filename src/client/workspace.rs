@@ -1,13 +1,18 @@
 use crate::client::Clients;
+use crate::event::{Event, WorkspaceChangedEvent};
 
 impl Clients {
     pub fn set_active_workspace(&mut self, workspace: u8) {
         tracing::debug!("set active workspace; workspace={}", workspace);
 
+        let previous = self.active_workspace;
         self.active_workspace = workspace;
 
-        for mut client in self.clients.iter_mut().filter(|c| c.controlled) {
-            if Some(self.active_workspace) == client.workspace {
+        // Floating windows are hidden too, only windows without a workspace such as docks stay
+        for mut client in self.clients.iter_mut().filter(|c| c.workspace.is_some()) {
+            let shown = Some(self.active_workspace) == client.workspace || client.sticky;
+
+            if shown && !client.minimized {
                 if !client.visible {
                     xcb::map_window(&self.conn, client.window);
                 }
@@ -16,6 +21,7 @@ impl Clients {
             } else {
                 if client.visible {
                     xcb::unmap_window(&self.conn, client.window);
+                    *self.pending_unmaps.entry(client.window).or_insert(0) += 1;
                 }
 
                 client.visible = false;
@@ -26,7 +32,33 @@ impl Clients {
 
         self.resize();
 
+        // Give input focus to the window last focused on the workspace, if it is still shown there
+        let is_visible = |clients: &Self, window: xcb::Window| {
+            clients
+                .clients
+                .iter()
+                .any(|c| c.window == window && c.visible)
+        };
+
+        let active_window = self
+            .active_window()
+            .filter(|&window| is_visible(self, window))
+            .or_else(|| {
+                self.workspace_focus_history(Some(workspace))
+                    .into_iter()
+                    .find(|&window| is_visible(self, window))
+            });
+
+        self.set_active_window(active_window);
+
         self.conn.flush();
+
+        if previous != workspace {
+            self.emit(Event::WorkspaceChanged(WorkspaceChangedEvent {
+                workspace,
+                previous,
+            }));
+        }
     }
 
     pub fn set_window_workspace(&mut self, window: xcb::Window, workspace: Option<u8>) {
@@ -36,9 +68,16 @@ impl Clients {
             return;
         }
 
+        let previous = match self.clients.iter().find(|c| c.window == window) {
+            Some(client) => client.workspace,
+            None => return,
+        };
+
         for client in self.clients.iter_mut() {
             if client.window == window {
                 client.workspace = workspace;
+                client.weight = 1.0;
+                client.sticky = false;
 
                 self.hide(window);
 
@@ -46,6 +85,17 @@ impl Clients {
             }
         }
 
+        // Focus moves to the window used before it on the workspace it left
+        self.refocus(window, previous);
+
+        self.set_wm_desktop(window);
+        self.set_net_wm_state(window);
+
+        // Dialogs move with the window they belong to
+        for transient in self.transients(window) {
+            self.set_window_workspace(transient, workspace);
+        }
+
         self.refresh_clients();
     }
 
@@ -71,8 +121,19 @@ impl Clients {
                     .replace('8', "⁸")
                     .replace('9', "⁹");
 
+                // Mark workspaces with windows that need attention
+                let urgent = if self
+                    .clients
+                    .iter()
+                    .any(|c| c.workspace == Some(i) && c.urgent)
+                {
+                    "!"
+                } else {
+                    ""
+                };
+
                 if count > 0 {
-                    format!("{}{}", i, count_string)
+                    format!("{}{}{}", i, count_string, urgent)
                 } else {
                     i.to_string()
                 }
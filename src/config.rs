@@ -1,4 +1,5 @@
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::fs;
 
 #[derive(Clone, Deserialize)]
@@ -8,6 +9,129 @@ pub enum Action {
     SelectBelow,
     ShrinkFront,
     ExpandFront,
+    IncMaster,
+    DecMaster,
+    OrientLeft,
+    OrientRight,
+    OrientTop,
+    OrientBottom,
+    OrientCenter,
+    NextOrientation,
+    GrowWindow,
+    ShrinkWindow,
+    EqualizeStack,
+    FocusLast,
+    CycleWindows,
+    CycleWindowsReverse,
+    Zoom,
+    MoveUp,
+    MoveDown,
+    RotateStackClockwise,
+    RotateStackCounterClockwise,
+    Minimize,
+    RestoreLast,
+    ToggleSticky,
+    ToggleFullscreen,
+    FocusUrgent,
+    FocusDirection(Direction),
+    SwapDirection(Direction),
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum FocusModel {
+    // Focus the window under the pointer, the root window takes focus when the pointer leaves
+    FollowsMouse,
+    // Focus the window the pointer enters, focus is kept when the pointer leaves
+    #[default]
+    Sloppy,
+    // Focus a window when clicked, the click is passed on to the window
+    Click,
+}
+
+// Where the front windows are placed, the other windows fill the rest of the screen
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Orientation {
+    #[default]
+    Left,
+    Right,
+    Top,
+    Bottom,
+    // Front windows in the middle with the other windows on both sides
+    Center,
+}
+
+// What happens to windows that do not fit on screen when tiled
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Overflow {
+    // Windows that do not fit share the last cell of their column or row
+    #[default]
+    Nest,
+    // Every window on the workspace fills the screen
+    Monocle,
+}
+
+// Whether new windows take focus from the focused window
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum FocusStealing {
+    // New windows are always focused
+    Allow,
+    // Windows opened before the last input are not focused, windows without a user time are
+    #[default]
+    Smart,
+    // Only windows with a user time after the last input are focused
+    Strict,
+}
+
+// Window types from _NET_WM_WINDOW_TYPE
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum WindowType {
+    Desktop,
+    Dock,
+    Toolbar,
+    Menu,
+    Utility,
+    Splash,
+    Dialog,
+    DropdownMenu,
+    PopupMenu,
+    Tooltip,
+    Notification,
+    Combo,
+    Dnd,
+    #[default]
+    Normal,
+}
+
+// Overrides how matching windows are managed, every condition given has to match
+#[derive(Clone, Debug, Deserialize)]
+pub struct Rule {
+    pub class: Option<String>,
+    pub instance: Option<String>,
+    // Matches titles containing the text
+    pub title: Option<String>,
+    pub window_type: Option<WindowType>,
+    pub managed: Option<bool>,
+    pub floating: Option<bool>,
+    pub focusable: Option<bool>,
+    pub border: Option<bool>,
+    pub center: Option<bool>,
+    pub below: Option<bool>,
+    pub workspace: Option<u8>,
+    // Focus when opened regardless of the focus stealing policy, or never
+    pub steal_focus: Option<bool>,
 }
 
 #[derive(Deserialize)]
@@ -17,11 +141,123 @@ pub struct ActionKeyPress {
     pub action: Action,
 }
 
+// A command given as a string run with `sh -c`, or as a list of arguments
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+pub enum CommandLine {
+    Shell(String),
+    Args(Vec<String>),
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Restart {
+    #[default]
+    Never,
+    OnFailure,
+    Always,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct AutostartProgram {
+    pub command: CommandLine,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    pub cwd: Option<String>,
+    #[serde(default)]
+    pub restart: Restart,
+    // Skip when the window manager is restarted in the same X session
+    #[serde(default)]
+    pub once: bool,
+    // Milliseconds to wait after the previous program before starting
+    #[serde(default)]
+    pub delay: u64,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Autostart {
+    Command(CommandLine),
+    Program(AutostartProgram),
+}
+
+impl Autostart {
+    pub fn program(&self) -> AutostartProgram {
+        match self {
+            Self::Command(command) => AutostartProgram {
+                command: command.clone(),
+                env: HashMap::new(),
+                cwd: None,
+                restart: Restart::Never,
+                once: false,
+                delay: 0,
+            },
+            Self::Program(program) => program.clone(),
+        }
+    }
+}
+
 #[derive(Deserialize)]
 pub struct Command {
     pub modifier: u16,
     pub keysym: u32,
-    pub command: String,
+    pub command: CommandLine,
+}
+
+#[derive(Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    WindowCreated,
+    WindowDestroyed,
+    FocusChanged,
+    WorkspaceChanged,
+    Fullscreen,
+    Startup,
+    Shutdown,
+}
+
+#[derive(Deserialize)]
+pub struct Hook {
+    pub event: HookEvent,
+    pub command: CommandLine,
+}
+
+#[derive(Deserialize)]
+pub struct Plugins {
+    #[serde(default = "default_plugins")]
+    pub enabled: Vec<String>,
+    #[serde(flatten)]
+    pub settings: HashMap<String, toml::Value>,
+}
+
+impl Default for Plugins {
+    fn default() -> Self {
+        Self {
+            enabled: default_plugins(),
+            settings: HashMap::new(),
+        }
+    }
+}
+
+fn default_plugins() -> Vec<String> {
+    [
+        "commands",
+        "configure_window",
+        "destroy_window",
+        "map_window",
+        "unmap_window",
+        "window_selector",
+        "window_sizer",
+        "window_state",
+        "urgency",
+        "user_time",
+        "workspaces",
+        "script",
+        "hooks",
+    ]
+    .iter()
+    .map(|name| name.to_string())
+    .collect()
 }
 
 #[derive(Deserialize)]
@@ -30,11 +266,43 @@ pub struct Config {
     pub border_gap: u32,
     pub active_border: u32,
     pub inactive_border: u32,
+    #[serde(default = "default_urgent_border")]
+    pub urgent_border: u32,
     pub workspace_modifier: u16,
     pub workspace_move_window_modifier: u16,
-    pub autostart: Vec<String>,
+    // Default placement of the front windows on each workspace
+    #[serde(default)]
+    pub orientation: Orientation,
+    #[serde(default)]
+    pub overflow: Overflow,
+    // Fill tiles exactly instead of following the size hints of windows
+    #[serde(default)]
+    pub ignore_size_hints: bool,
+    // Unmap the dock while a full screen window is shown
+    #[serde(default)]
+    pub full_screen_hides_dock: bool,
+    #[serde(default)]
+    pub focus_model: FocusModel,
+    #[serde(default)]
+    pub focus_stealing: FocusStealing,
+    // Move the pointer to windows focused with the keyboard
+    #[serde(default)]
+    pub warp_pointer: bool,
+    pub autostart: Vec<Autostart>,
+    // File the output of spawned commands is written to
+    pub command_log: Option<String>,
     pub actions: Vec<ActionKeyPress>,
     pub commands: Vec<Command>,
+    #[serde(default)]
+    pub hooks: Vec<Hook>,
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+    #[serde(default)]
+    pub plugins: Plugins,
+}
+
+fn default_urgent_border() -> u32 {
+    0xe06c75
 }
 
 pub fn get_config() -> Config {
@@ -50,3 +318,84 @@ pub fn get_config() -> Config {
 
     toml::from_str(&toml_string).expect("Unable to parse toml config.")
 }
+
+// Expand a leading `~` and `$VAR` or `${VAR}` environment variables
+pub fn expand(value: &str) -> String {
+    let value = match value.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => {
+            let home_path = std::env::var("HOME").unwrap_or_default();
+            format!("{}{}", home_path, rest)
+        }
+        _ => value.to_string(),
+    };
+
+    let mut expanded = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            expanded.push(c);
+            continue;
+        }
+
+        let braced = chars.peek() == Some(&'{');
+        if braced {
+            chars.next();
+        }
+
+        let mut name = String::new();
+        while let Some(&c) = chars.peek() {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                name.push(c);
+                chars.next();
+            } else {
+                break;
+            }
+        }
+
+        if braced && chars.next_if_eq(&'}').is_none() {
+            // Leave malformed variables untouched
+            expanded.push_str("${");
+            expanded.push_str(&name);
+        } else if name.is_empty() {
+            expanded.push('$');
+        } else {
+            expanded.push_str(&std::env::var(&name).unwrap_or_default());
+        }
+    }
+
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::expand;
+
+    #[test]
+    fn expands_home() {
+        std::env::set_var("HOME", "/home/mwm");
+
+        assert_eq!(expand("~"), "/home/mwm");
+        assert_eq!(expand("~/bin/bar"), "/home/mwm/bin/bar");
+        // Only a leading `~` on its own or before a slash is the home directory
+        assert_eq!(expand("~user/bin"), "~user/bin");
+        assert_eq!(expand("a/~/b"), "a/~/b");
+    }
+
+    #[test]
+    fn expands_variables() {
+        std::env::set_var("MWM_TEST_DIR", "/opt/mwm");
+
+        assert_eq!(expand("$MWM_TEST_DIR/bar"), "/opt/mwm/bar");
+        assert_eq!(expand("${MWM_TEST_DIR}bar"), "/opt/mwmbar");
+        assert_eq!(expand("$MWM_TEST_UNSET/bar"), "/bar");
+    }
+
+    #[test]
+    fn leaves_malformed_variables() {
+        assert_eq!(expand("$"), "$");
+        assert_eq!(expand("a $ b"), "a $ b");
+        assert_eq!(expand("${MWM_TEST_DIR"), "${MWM_TEST_DIR");
+        assert_eq!(expand("${}"), "$");
+    }
+}
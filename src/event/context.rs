@@ -0,0 +1,26 @@
+use crate::client::Clients;
+use crate::config::Config;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+#[derive(Clone)]
+pub struct EventContext<E> {
+    pub clients: Arc<Mutex<Clients>>,
+    pub config: Arc<Config>,
+    pub conn: Arc<xcb_util::ewmh::Connection>,
+    pub event: E,
+}
+
+impl<E> EventContext<E> {
+    pub fn with_event<T>(&self, event: T) -> EventContext<T> {
+        EventContext {
+            clients: self.clients.clone(),
+            config: self.config.clone(),
+            conn: self.conn.clone(),
+            event,
+        }
+    }
+
+    pub fn lock_clients(&self) -> MutexGuard<'_, Clients> {
+        Clients::lock(&self.clients)
+    }
+}
@@ -0,0 +1,394 @@
+use crate::event::types::*;
+use std::convert::TryInto;
+
+impl Event {
+    pub fn decode(conn: &xcb::Connection, event: &xcb::GenericEvent) -> Option<Self> {
+        let response_type = event.response_type() & !0x80;
+
+        // Safety: every cast is guarded by the response type of the event, and the
+        // fields are copied out before the generic event is freed.
+        let decoded = unsafe {
+            match response_type {
+                xcb::CLIENT_MESSAGE => {
+                    Event::ClientMessage(xcb::cast_event::<xcb::ClientMessageEvent>(event).into())
+                }
+                xcb::KEY_PRESS => {
+                    Event::KeyPress(xcb::cast_event::<xcb::KeyPressEvent>(event).into())
+                }
+                xcb::KEY_RELEASE => {
+                    Event::KeyRelease(xcb::cast_event::<xcb::KeyReleaseEvent>(event).into())
+                }
+                xcb::BUTTON_PRESS => {
+                    Event::ButtonPress(xcb::cast_event::<xcb::ButtonPressEvent>(event).into())
+                }
+                xcb::BUTTON_RELEASE => {
+                    Event::ButtonRelease(xcb::cast_event::<xcb::ButtonReleaseEvent>(event).into())
+                }
+                xcb::MOTION_NOTIFY => {
+                    Event::MotionNotify(xcb::cast_event::<xcb::MotionNotifyEvent>(event).into())
+                }
+                xcb::CONFIGURE_REQUEST => Event::ConfigureRequest(
+                    xcb::cast_event::<xcb::ConfigureRequestEvent>(event).into(),
+                ),
+                xcb::CONFIGURE_NOTIFY => Event::ConfigureNotify(
+                    xcb::cast_event::<xcb::ConfigureNotifyEvent>(event).into(),
+                ),
+                xcb::CREATE_NOTIFY => {
+                    Event::CreateNotify(xcb::cast_event::<xcb::CreateNotifyEvent>(event).into())
+                }
+                xcb::MAP_REQUEST => {
+                    Event::MapRequest(xcb::cast_event::<xcb::MapRequestEvent>(event).into())
+                }
+                xcb::MAPPING_NOTIFY => {
+                    Event::MappingNotify(xcb::cast_event::<xcb::MappingNotifyEvent>(event).into())
+                }
+                xcb::PROPERTY_NOTIFY => {
+                    Event::PropertyNotify(xcb::cast_event::<xcb::PropertyNotifyEvent>(event).into())
+                }
+                xcb::ENTER_NOTIFY => {
+                    Event::EnterNotify(xcb::cast_event::<xcb::EnterNotifyEvent>(event).into())
+                }
+                xcb::LEAVE_NOTIFY => {
+                    Event::LeaveNotify(xcb::cast_event::<xcb::LeaveNotifyEvent>(event).into())
+                }
+                xcb::FOCUS_IN => Event::FocusIn(xcb::cast_event::<xcb::FocusInEvent>(event).into()),
+                xcb::FOCUS_OUT => {
+                    Event::FocusOut(xcb::cast_event::<xcb::FocusOutEvent>(event).into())
+                }
+                xcb::EXPOSE => Event::Expose(xcb::cast_event::<xcb::ExposeEvent>(event).into()),
+                xcb::UNMAP_NOTIFY => {
+                    Event::UnmapNotify(xcb::cast_event::<xcb::UnmapNotifyEvent>(event).into())
+                }
+                xcb::DESTROY_NOTIFY => {
+                    Event::DestroyNotify(xcb::cast_event::<xcb::DestroyNotifyEvent>(event).into())
+                }
+                _ => return Self::decode_extension(conn, event, response_type),
+            }
+        };
+
+        Some(decoded)
+    }
+
+    fn decode_extension(
+        conn: &xcb::Connection,
+        event: &xcb::GenericEvent,
+        response_type: u8,
+    ) -> Option<Self> {
+        let randr_base = conn
+            .get_extension_data(xcb::randr::id())
+            .filter(|data| data.present())
+            .map(|data| data.first_event());
+
+        let xkb_base = conn
+            .get_extension_data(xcb::xkb::id())
+            .filter(|data| data.present())
+            .map(|data| data.first_event());
+
+        if let Some(base) = randr_base {
+            // Safety: the casts are guarded by the RandR event codes
+            let decoded = unsafe {
+                match response_type.wrapping_sub(base) {
+                    xcb::randr::SCREEN_CHANGE_NOTIFY => Some(Event::RandrScreenChangeNotify(
+                        xcb::cast_event::<xcb::randr::ScreenChangeNotifyEvent>(event).into(),
+                    )),
+                    xcb::randr::NOTIFY => Some(Event::RandrNotify(
+                        xcb::cast_event::<xcb::randr::NotifyEvent>(event).into(),
+                    )),
+                    _ => None,
+                }
+            };
+
+            if decoded.is_some() {
+                return decoded;
+            }
+        }
+
+        if Some(response_type) != xkb_base {
+            // Events we do not care about
+            return None;
+        }
+
+        // Safety: all XKB events share one event code and have the XKB type as the
+        // second byte, the casts are guarded by that type.
+        unsafe {
+            let xkb_type = xcb::cast_event::<xcb::xkb::StateNotifyEvent>(event).xkb_type();
+
+            match xkb_type {
+                xcb::xkb::NEW_KEYBOARD_NOTIFY => Some(Event::XkbNewKeyboardNotify(
+                    xcb::cast_event::<xcb::xkb::NewKeyboardNotifyEvent>(event).into(),
+                )),
+                xcb::xkb::MAP_NOTIFY => Some(Event::XkbMapNotify(
+                    xcb::cast_event::<xcb::xkb::MapNotifyEvent>(event).into(),
+                )),
+                xcb::xkb::STATE_NOTIFY => Some(Event::XkbStateNotify(
+                    xcb::cast_event::<xcb::xkb::StateNotifyEvent>(event).into(),
+                )),
+                _ => None,
+            }
+        }
+    }
+}
+
+impl From<&xcb::ClientMessageEvent> for ClientMessageEvent {
+    fn from(event: &xcb::ClientMessageEvent) -> Self {
+        Self {
+            format: event.format(),
+            window: event.window(),
+            type_: event.type_(),
+            data: event.data().data8().try_into().unwrap_or([0; 20]),
+        }
+    }
+}
+
+impl From<&xcb::KeyPressEvent> for KeyEvent {
+    fn from(event: &xcb::KeyPressEvent) -> Self {
+        Self {
+            detail: event.detail(),
+            time: event.time(),
+            root: event.root(),
+            event: event.event(),
+            child: event.child(),
+            root_x: event.root_x(),
+            root_y: event.root_y(),
+            event_x: event.event_x(),
+            event_y: event.event_y(),
+            state: event.state(),
+            same_screen: event.same_screen(),
+        }
+    }
+}
+
+impl From<&xcb::ButtonPressEvent> for ButtonEvent {
+    fn from(event: &xcb::ButtonPressEvent) -> Self {
+        Self {
+            detail: event.detail(),
+            time: event.time(),
+            root: event.root(),
+            event: event.event(),
+            child: event.child(),
+            root_x: event.root_x(),
+            root_y: event.root_y(),
+            event_x: event.event_x(),
+            event_y: event.event_y(),
+            state: event.state(),
+            same_screen: event.same_screen(),
+        }
+    }
+}
+
+impl From<&xcb::MotionNotifyEvent> for MotionNotifyEvent {
+    fn from(event: &xcb::MotionNotifyEvent) -> Self {
+        Self {
+            detail: event.detail(),
+            time: event.time(),
+            root: event.root(),
+            event: event.event(),
+            child: event.child(),
+            root_x: event.root_x(),
+            root_y: event.root_y(),
+            event_x: event.event_x(),
+            event_y: event.event_y(),
+            state: event.state(),
+            same_screen: event.same_screen(),
+        }
+    }
+}
+
+impl From<&xcb::ConfigureRequestEvent> for ConfigureRequestEvent {
+    fn from(event: &xcb::ConfigureRequestEvent) -> Self {
+        Self {
+            stack_mode: event.stack_mode(),
+            parent: event.parent(),
+            window: event.window(),
+            sibling: event.sibling(),
+            x: event.x(),
+            y: event.y(),
+            width: event.width(),
+            height: event.height(),
+            border_width: event.border_width(),
+            value_mask: event.value_mask(),
+        }
+    }
+}
+
+impl From<&xcb::ConfigureNotifyEvent> for ConfigureNotifyEvent {
+    fn from(event: &xcb::ConfigureNotifyEvent) -> Self {
+        Self {
+            event: event.event(),
+            window: event.window(),
+            above_sibling: event.above_sibling(),
+            x: event.x(),
+            y: event.y(),
+            width: event.width(),
+            height: event.height(),
+            border_width: event.border_width(),
+            override_redirect: event.override_redirect(),
+        }
+    }
+}
+
+impl From<&xcb::CreateNotifyEvent> for CreateNotifyEvent {
+    fn from(event: &xcb::CreateNotifyEvent) -> Self {
+        Self {
+            parent: event.parent(),
+            window: event.window(),
+            x: event.x(),
+            y: event.y(),
+            width: event.width(),
+            height: event.height(),
+            border_width: event.border_width(),
+            override_redirect: event.override_redirect(),
+        }
+    }
+}
+
+impl From<&xcb::MapRequestEvent> for MapRequestEvent {
+    fn from(event: &xcb::MapRequestEvent) -> Self {
+        Self {
+            parent: event.parent(),
+            window: event.window(),
+        }
+    }
+}
+
+impl From<&xcb::MappingNotifyEvent> for MappingNotifyEvent {
+    fn from(event: &xcb::MappingNotifyEvent) -> Self {
+        Self {
+            request: event.request(),
+            first_keycode: event.first_keycode(),
+            count: event.count(),
+        }
+    }
+}
+
+impl From<&xcb::PropertyNotifyEvent> for PropertyNotifyEvent {
+    fn from(event: &xcb::PropertyNotifyEvent) -> Self {
+        Self {
+            window: event.window(),
+            atom: event.atom(),
+            time: event.time(),
+            state: event.state(),
+        }
+    }
+}
+
+impl From<&xcb::EnterNotifyEvent> for CrossingEvent {
+    fn from(event: &xcb::EnterNotifyEvent) -> Self {
+        Self {
+            detail: event.detail(),
+            time: event.time(),
+            root: event.root(),
+            event: event.event(),
+            child: event.child(),
+            root_x: event.root_x(),
+            root_y: event.root_y(),
+            event_x: event.event_x(),
+            event_y: event.event_y(),
+            state: event.state(),
+            mode: event.mode(),
+            same_screen_focus: event.same_screen_focus(),
+        }
+    }
+}
+
+impl From<&xcb::FocusInEvent> for FocusEvent {
+    fn from(event: &xcb::FocusInEvent) -> Self {
+        Self {
+            detail: event.detail(),
+            event: event.event(),
+            mode: event.mode(),
+        }
+    }
+}
+
+impl From<&xcb::ExposeEvent> for ExposeEvent {
+    fn from(event: &xcb::ExposeEvent) -> Self {
+        Self {
+            window: event.window(),
+            x: event.x(),
+            y: event.y(),
+            width: event.width(),
+            height: event.height(),
+            count: event.count(),
+        }
+    }
+}
+
+impl From<&xcb::UnmapNotifyEvent> for UnmapNotifyEvent {
+    fn from(event: &xcb::UnmapNotifyEvent) -> Self {
+        Self {
+            event: event.event(),
+            window: event.window(),
+            from_configure: event.from_configure(),
+        }
+    }
+}
+
+impl From<&xcb::DestroyNotifyEvent> for DestroyNotifyEvent {
+    fn from(event: &xcb::DestroyNotifyEvent) -> Self {
+        Self {
+            event: event.event(),
+            window: event.window(),
+        }
+    }
+}
+
+impl From<&xcb::randr::ScreenChangeNotifyEvent> for RandrScreenChangeNotifyEvent {
+    fn from(event: &xcb::randr::ScreenChangeNotifyEvent) -> Self {
+        Self {
+            rotation: event.rotation(),
+            timestamp: event.timestamp(),
+            root: event.root(),
+            width: event.width(),
+            height: event.height(),
+        }
+    }
+}
+
+impl From<&xcb::randr::NotifyEvent> for RandrNotifyEvent {
+    fn from(event: &xcb::randr::NotifyEvent) -> Self {
+        Self {
+            sub_code: event.sub_code(),
+        }
+    }
+}
+
+impl From<&xcb::xkb::NewKeyboardNotifyEvent> for XkbNewKeyboardNotifyEvent {
+    fn from(event: &xcb::xkb::NewKeyboardNotifyEvent) -> Self {
+        Self {
+            time: event.time(),
+            device_id: event.device_id(),
+            old_device_id: event.old_device_id(),
+            min_key_code: event.min_key_code(),
+            max_key_code: event.max_key_code(),
+            changed: event.changed(),
+        }
+    }
+}
+
+impl From<&xcb::xkb::MapNotifyEvent> for XkbMapNotifyEvent {
+    fn from(event: &xcb::xkb::MapNotifyEvent) -> Self {
+        Self {
+            time: event.time(),
+            device_id: event.device_id(),
+            changed: event.changed(),
+            min_key_code: event.min_key_code(),
+            max_key_code: event.max_key_code(),
+        }
+    }
+}
+
+impl From<&xcb::xkb::StateNotifyEvent> for XkbStateNotifyEvent {
+    fn from(event: &xcb::xkb::StateNotifyEvent) -> Self {
+        Self {
+            time: event.time(),
+            device_id: event.device_id(),
+            mods: event.mods(),
+            base_mods: event.base_mods(),
+            latched_mods: event.latched_mods(),
+            locked_mods: event.locked_mods(),
+            group: event.group(),
+            changed: event.changed(),
+        }
+    }
+}
@@ -0,0 +1,6 @@
+mod context;
+mod decode;
+mod types;
+
+pub use context::EventContext;
+pub use types::*;
@@ -0,0 +1,287 @@
+use serde::{Deserialize, Serialize};
+use std::convert::TryInto;
+
+/// An owned copy of an X event, decoded once when it is received.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum Event {
+    ClientMessage(ClientMessageEvent),
+    KeyPress(KeyEvent),
+    KeyRelease(KeyEvent),
+    ButtonPress(ButtonEvent),
+    ButtonRelease(ButtonEvent),
+    MotionNotify(MotionNotifyEvent),
+    ConfigureRequest(ConfigureRequestEvent),
+    ConfigureNotify(ConfigureNotifyEvent),
+    CreateNotify(CreateNotifyEvent),
+    MapRequest(MapRequestEvent),
+    MappingNotify(MappingNotifyEvent),
+    PropertyNotify(PropertyNotifyEvent),
+    EnterNotify(CrossingEvent),
+    LeaveNotify(CrossingEvent),
+    FocusIn(FocusEvent),
+    FocusOut(FocusEvent),
+    Expose(ExposeEvent),
+    UnmapNotify(UnmapNotifyEvent),
+    DestroyNotify(DestroyNotifyEvent),
+    RandrScreenChangeNotify(RandrScreenChangeNotifyEvent),
+    RandrNotify(RandrNotifyEvent),
+    XkbNewKeyboardNotify(XkbNewKeyboardNotifyEvent),
+    XkbMapNotify(XkbMapNotifyEvent),
+    XkbStateNotify(XkbStateNotifyEvent),
+    // Events emitted by the window manager itself
+    Startup,
+    Shutdown,
+    WindowCreated(WindowCreatedEvent),
+    WindowDestroyed(WindowDestroyedEvent),
+    FocusChanged(FocusChangedEvent),
+    WorkspaceChanged(WorkspaceChangedEvent),
+    FullScreen(FullScreenEvent),
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ClientMessageEvent {
+    pub format: u8,
+    pub window: xcb::Window,
+    pub type_: xcb::Atom,
+    pub data: [u8; 20],
+}
+
+impl ClientMessageEvent {
+    pub fn data32(&self) -> [u32; 5] {
+        let mut data = [0; 5];
+
+        for (value, bytes) in data.iter_mut().zip(self.data.chunks_exact(4)) {
+            *value = u32::from_ne_bytes(bytes.try_into().unwrap());
+        }
+
+        data
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct KeyEvent {
+    pub detail: xcb::Keycode,
+    pub time: xcb::Timestamp,
+    pub root: xcb::Window,
+    pub event: xcb::Window,
+    pub child: xcb::Window,
+    pub root_x: i16,
+    pub root_y: i16,
+    pub event_x: i16,
+    pub event_y: i16,
+    pub state: u16,
+    pub same_screen: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ButtonEvent {
+    pub detail: xcb::Button,
+    pub time: xcb::Timestamp,
+    pub root: xcb::Window,
+    pub event: xcb::Window,
+    pub child: xcb::Window,
+    pub root_x: i16,
+    pub root_y: i16,
+    pub event_x: i16,
+    pub event_y: i16,
+    pub state: u16,
+    pub same_screen: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MotionNotifyEvent {
+    pub detail: u8,
+    pub time: xcb::Timestamp,
+    pub root: xcb::Window,
+    pub event: xcb::Window,
+    pub child: xcb::Window,
+    pub root_x: i16,
+    pub root_y: i16,
+    pub event_x: i16,
+    pub event_y: i16,
+    pub state: u16,
+    pub same_screen: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ConfigureRequestEvent {
+    pub stack_mode: u8,
+    pub parent: xcb::Window,
+    pub window: xcb::Window,
+    pub sibling: xcb::Window,
+    pub x: i16,
+    pub y: i16,
+    pub width: u16,
+    pub height: u16,
+    pub border_width: u16,
+    pub value_mask: u16,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ConfigureNotifyEvent {
+    pub event: xcb::Window,
+    pub window: xcb::Window,
+    pub above_sibling: xcb::Window,
+    pub x: i16,
+    pub y: i16,
+    pub width: u16,
+    pub height: u16,
+    pub border_width: u16,
+    pub override_redirect: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CreateNotifyEvent {
+    pub parent: xcb::Window,
+    pub window: xcb::Window,
+    pub x: i16,
+    pub y: i16,
+    pub width: u16,
+    pub height: u16,
+    pub border_width: u16,
+    pub override_redirect: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MapRequestEvent {
+    pub parent: xcb::Window,
+    pub window: xcb::Window,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MappingNotifyEvent {
+    pub request: u8,
+    pub first_keycode: xcb::Keycode,
+    pub count: u8,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PropertyNotifyEvent {
+    pub window: xcb::Window,
+    pub atom: xcb::Atom,
+    pub time: xcb::Timestamp,
+    pub state: u8,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CrossingEvent {
+    pub detail: u8,
+    pub time: xcb::Timestamp,
+    pub root: xcb::Window,
+    pub event: xcb::Window,
+    pub child: xcb::Window,
+    pub root_x: i16,
+    pub root_y: i16,
+    pub event_x: i16,
+    pub event_y: i16,
+    pub state: u16,
+    pub mode: u8,
+    pub same_screen_focus: u8,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FocusEvent {
+    pub detail: u8,
+    pub event: xcb::Window,
+    pub mode: u8,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ExposeEvent {
+    pub window: xcb::Window,
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+    pub count: u16,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct UnmapNotifyEvent {
+    pub event: xcb::Window,
+    pub window: xcb::Window,
+    pub from_configure: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DestroyNotifyEvent {
+    pub event: xcb::Window,
+    pub window: xcb::Window,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RandrScreenChangeNotifyEvent {
+    pub rotation: u8,
+    pub timestamp: xcb::Timestamp,
+    pub root: xcb::Window,
+    pub width: u16,
+    pub height: u16,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RandrNotifyEvent {
+    pub sub_code: u8,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct XkbNewKeyboardNotifyEvent {
+    pub time: xcb::Timestamp,
+    pub device_id: u8,
+    pub old_device_id: u8,
+    pub min_key_code: xcb::Keycode,
+    pub max_key_code: xcb::Keycode,
+    pub changed: u16,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct XkbMapNotifyEvent {
+    pub time: xcb::Timestamp,
+    pub device_id: u8,
+    pub changed: u16,
+    pub min_key_code: xcb::Keycode,
+    pub max_key_code: xcb::Keycode,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct XkbStateNotifyEvent {
+    pub time: xcb::Timestamp,
+    pub device_id: u8,
+    pub mods: u8,
+    pub base_mods: u8,
+    pub latched_mods: u8,
+    pub locked_mods: u8,
+    pub group: u8,
+    pub changed: u16,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct WindowCreatedEvent {
+    pub window: xcb::Window,
+    pub workspace: Option<u8>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct WindowDestroyedEvent {
+    pub window: xcb::Window,
+    pub workspace: Option<u8>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FocusChangedEvent {
+    pub window: Option<xcb::Window>,
+    pub previous: Option<xcb::Window>,
+    pub workspace: u8,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct WorkspaceChangedEvent {
+    pub workspace: u8,
+    pub previous: u8,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FullScreenEvent {
+    pub window: xcb::Window,
+    pub workspace: Option<u8>,
+    pub full_screen: bool,
+}
@@ -1,111 +1,460 @@
-use crate::event::EventContext;
+use crate::config::Config;
+use crate::event::*;
 use crate::plugin::PluginHandler;
 use crate::plugins;
+use anyhow::Result;
+use std::panic::{self, AssertUnwindSafe};
+
+// A failing or panicking plugin is logged, the other plugins still get the event
+fn run_plugin(f: impl FnOnce() -> Result<()>) {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => tracing::error!("plugin failed; error={:#}", e),
+        Err(_) => tracing::error!("plugin panicked"),
+    }
+}
 
 pub struct Handler {
-    plugins: Vec<Box<dyn PluginHandler>>,
+    plugins: Vec<Box<dyn PluginHandler + Send>>,
 }
 
-impl Default for Handler {
-    fn default() -> Self {
-        Self {
-            plugins: vec![
-                Box::new(plugins::Commands::default()),
-                Box::new(plugins::ConfigureWindow::default()),
-                Box::new(plugins::DestroyWindow::default()),
-                Box::new(plugins::MapWindow::default()),
-                Box::new(plugins::UnmapWindow::default()),
-                Box::new(plugins::WindowSelector::default()),
-                Box::new(plugins::WindowSizer::default()),
-                Box::new(plugins::Workspaces::default()),
-            ],
+impl Handler {
+    pub fn new(config: &Config) -> Result<Self> {
+        let enabled = &config.plugins.enabled;
+
+        for name in plugins::PROTOCOL_PLUGINS {
+            if !enabled.iter().any(|enabled| enabled == name) {
+                tracing::warn!(
+                    "protocol plugin not enabled, windows relying on it will misbehave; plugin={}",
+                    name
+                );
+            }
         }
+
+        let plugins = enabled
+            .iter()
+            .map(|name| plugins::from_name(name, config.plugins.settings.get(name)))
+            .collect::<Result<Vec<_>>>()?;
+
+        tracing::info!("loaded plugins; plugins={:?}", enabled);
+
+        Ok(Self { plugins })
     }
-}
 
-impl Handler {
-    #[tracing::instrument(skip_all, fields(window = ectx.event.window()))]
-    pub fn on_client_message(&mut self, ectx: EventContext<xcb::ClientMessageEvent>) {
+    #[tracing::instrument(skip_all)]
+    pub fn handle(&mut self, ectx: EventContext<Event>) {
+        tracing::trace!(event = ?ectx.event);
+
+        self.plugins
+            .iter_mut()
+            .for_each(|plugin| run_plugin(|| plugin.on_event(ectx.clone())));
+
+        match &ectx.event {
+            Event::ClientMessage(event) => self.on_client_message(ectx.with_event(event.clone())),
+            Event::KeyPress(event) => self.on_key_press(ectx.with_event(event.clone())),
+            Event::KeyRelease(event) => self.on_key_release(ectx.with_event(event.clone())),
+            Event::ButtonPress(event) => self.on_button_press(ectx.with_event(event.clone())),
+            Event::ButtonRelease(event) => self.on_button_release(ectx.with_event(event.clone())),
+            Event::MotionNotify(event) => self.on_motion_notify(ectx.with_event(event.clone())),
+            Event::ConfigureRequest(event) => {
+                self.on_configure_request(ectx.with_event(event.clone()))
+            }
+            Event::ConfigureNotify(event) => {
+                self.on_configure_notify(ectx.with_event(event.clone()))
+            }
+            Event::CreateNotify(event) => self.on_create_notify(ectx.with_event(event.clone())),
+            Event::MapRequest(event) => self.on_map_request(ectx.with_event(event.clone())),
+            Event::MappingNotify(event) => self.on_mapping_notify(ectx.with_event(event.clone())),
+            Event::PropertyNotify(event) => self.on_property_notify(ectx.with_event(event.clone())),
+            Event::EnterNotify(event) => self.on_enter_notify(ectx.with_event(event.clone())),
+            Event::LeaveNotify(event) => self.on_leave_notify(ectx.with_event(event.clone())),
+            Event::FocusIn(event) => self.on_focus_in(ectx.with_event(event.clone())),
+            Event::FocusOut(event) => self.on_focus_out(ectx.with_event(event.clone())),
+            Event::Expose(event) => self.on_expose(ectx.with_event(event.clone())),
+            Event::UnmapNotify(event) => self.on_unmap_notify(ectx.with_event(event.clone())),
+            Event::DestroyNotify(event) => self.on_destroy_notify(ectx.with_event(event.clone())),
+            Event::RandrScreenChangeNotify(event) => {
+                self.on_randr_screen_change_notify(ectx.with_event(event.clone()))
+            }
+            Event::RandrNotify(event) => self.on_randr_notify(ectx.with_event(event.clone())),
+            Event::XkbNewKeyboardNotify(event) => {
+                self.on_xkb_new_keyboard_notify(ectx.with_event(event.clone()))
+            }
+            Event::XkbMapNotify(event) => self.on_xkb_map_notify(ectx.with_event(event.clone())),
+            Event::XkbStateNotify(event) => {
+                self.on_xkb_state_notify(ectx.with_event(event.clone()))
+            }
+            Event::Startup => self.on_startup(ectx.with_event(())),
+            Event::Shutdown => self.on_shutdown(ectx.with_event(())),
+            Event::WindowCreated(event) => self.on_window_created(ectx.with_event(event.clone())),
+            Event::WindowDestroyed(event) => {
+                self.on_window_destroyed(ectx.with_event(event.clone()))
+            }
+            Event::FocusChanged(event) => self.on_focus_changed(ectx.with_event(event.clone())),
+            Event::WorkspaceChanged(event) => {
+                self.on_workspace_changed(ectx.with_event(event.clone()))
+            }
+            Event::FullScreen(event) => self.on_full_screen(ectx.with_event(event.clone())),
+        }
+    }
+
+    #[tracing::instrument(skip_all, fields(window = ectx.event.window))]
+    fn on_client_message(&mut self, ectx: EventContext<ClientMessageEvent>) {
+        self.plugins
+            .iter_mut()
+            .for_each(|plugin| run_plugin(|| plugin.on_client_message(ectx.clone())));
+    }
+
+    #[tracing::instrument(
+        skip_all,
+        fields(
+            event_window = ectx.event.event,
+            key_code = ectx.event.detail,
+            state = ectx.event.state,
+        )
+    )]
+    fn on_key_press(&mut self, ectx: EventContext<KeyEvent>) {
+        self.plugins
+            .iter_mut()
+            .for_each(|plugin| run_plugin(|| plugin.on_key_press(ectx.clone())));
+    }
+
+    #[tracing::instrument(
+        skip_all,
+        fields(
+            event_window = ectx.event.event,
+            key_code = ectx.event.detail,
+            state = ectx.event.state,
+        )
+    )]
+    fn on_key_release(&mut self, ectx: EventContext<KeyEvent>) {
+        self.plugins
+            .iter_mut()
+            .for_each(|plugin| run_plugin(|| plugin.on_key_release(ectx.clone())));
+    }
+
+    #[tracing::instrument(
+        skip_all,
+        fields(
+            event_window = ectx.event.event,
+            button = ectx.event.detail,
+            state = ectx.event.state,
+        )
+    )]
+    fn on_button_press(&mut self, ectx: EventContext<ButtonEvent>) {
+        self.plugins
+            .iter_mut()
+            .for_each(|plugin| run_plugin(|| plugin.on_button_press(ectx.clone())));
+    }
+
+    #[tracing::instrument(
+        skip_all,
+        fields(
+            event_window = ectx.event.event,
+            button = ectx.event.detail,
+            state = ectx.event.state,
+        )
+    )]
+    fn on_button_release(&mut self, ectx: EventContext<ButtonEvent>) {
+        self.plugins
+            .iter_mut()
+            .for_each(|plugin| run_plugin(|| plugin.on_button_release(ectx.clone())));
+    }
+
+    #[tracing::instrument(
+        skip_all,
+        fields(
+            event_window = ectx.event.event,
+            root_x = ectx.event.root_x,
+            root_y = ectx.event.root_y,
+        )
+    )]
+    fn on_motion_notify(&mut self, ectx: EventContext<MotionNotifyEvent>) {
+        self.plugins
+            .iter_mut()
+            .for_each(|plugin| run_plugin(|| plugin.on_motion_notify(ectx.clone())));
+    }
+
+    #[tracing::instrument(
+        skip_all,
+        fields(
+            window = ectx.event.window,
+            width = ectx.event.width,
+            height = ectx.event.height,
+            x = ectx.event.x,
+            y = ectx.event.y,
+        )
+    )]
+    fn on_configure_request(&mut self, ectx: EventContext<ConfigureRequestEvent>) {
+        self.plugins
+            .iter_mut()
+            .for_each(|plugin| run_plugin(|| plugin.on_configure_request(ectx.clone())));
+    }
+
+    #[tracing::instrument(
+        skip_all,
+        fields(
+            window = ectx.event.window,
+            width = ectx.event.width,
+            height = ectx.event.height,
+            x = ectx.event.x,
+            y = ectx.event.y,
+        )
+    )]
+    fn on_configure_notify(&mut self, ectx: EventContext<ConfigureNotifyEvent>) {
+        self.plugins
+            .iter_mut()
+            .for_each(|plugin| run_plugin(|| plugin.on_configure_notify(ectx.clone())));
+    }
+
+    #[tracing::instrument(skip_all, fields(window = ectx.event.window))]
+    fn on_create_notify(&mut self, ectx: EventContext<CreateNotifyEvent>) {
+        self.plugins
+            .iter_mut()
+            .for_each(|plugin| run_plugin(|| plugin.on_create_notify(ectx.clone())));
+    }
+
+    #[tracing::instrument(skip_all, fields(window = ectx.event.window))]
+    fn on_map_request(&mut self, ectx: EventContext<MapRequestEvent>) {
+        self.plugins
+            .iter_mut()
+            .for_each(|plugin| run_plugin(|| plugin.on_map_request(ectx.clone())));
+    }
+
+    #[tracing::instrument(
+        skip_all,
+        fields(
+            request = ectx.event.request,
+            first_keycode = ectx.event.first_keycode,
+            count = ectx.event.count,
+        )
+    )]
+    fn on_mapping_notify(&mut self, ectx: EventContext<MappingNotifyEvent>) {
         self.plugins
             .iter_mut()
-            .for_each(|plugin| plugin.on_client_message(ectx.clone()).unwrap());
+            .for_each(|plugin| run_plugin(|| plugin.on_mapping_notify(ectx.clone())));
     }
 
     #[tracing::instrument(
         skip_all,
         fields(
-            event_window = ectx.event.event(),
-            key_code = ectx.event.detail(),
-            state = ectx.event.state()
+            window = ectx.event.window,
+            atom = ectx.event.atom,
         )
     )]
-    pub fn on_key_press(&mut self, ectx: EventContext<xcb::KeyPressEvent>) {
+    fn on_property_notify(&mut self, ectx: EventContext<PropertyNotifyEvent>) {
         self.plugins
             .iter_mut()
-            .for_each(|plugin| plugin.on_key_press(ectx.clone()).unwrap());
+            .for_each(|plugin| run_plugin(|| plugin.on_property_notify(ectx.clone())));
     }
 
     #[tracing::instrument(
         skip_all,
         fields(
-            window = ectx.event.window(),
-            width = ectx.event.width(),
-            height = ectx.event.height(),
-            x = ectx.event.x(),
-            y = ectx.event.y()
+            event_window = ectx.event.event,
+            state = ectx.event.state,
+            mode = ectx.event.mode,
         )
     )]
-    pub fn on_configure_request(&mut self, ectx: EventContext<xcb::ConfigureRequestEvent>) {
+    fn on_enter_notify(&mut self, ectx: EventContext<CrossingEvent>) {
         self.plugins
             .iter_mut()
-            .for_each(|plugin| plugin.on_configure_request(ectx.clone()).unwrap());
+            .for_each(|plugin| run_plugin(|| plugin.on_enter_notify(ectx.clone())));
     }
 
-    #[tracing::instrument(skip_all, fields(window = ectx.event.window()))]
-    pub fn on_map_request(&mut self, ectx: EventContext<xcb::MapRequestEvent>) {
+    #[tracing::instrument(
+        skip_all,
+        fields(
+            event_window = ectx.event.event,
+            state = ectx.event.state,
+            mode = ectx.event.mode,
+        )
+    )]
+    fn on_leave_notify(&mut self, ectx: EventContext<CrossingEvent>) {
         self.plugins
             .iter_mut()
-            .for_each(|plugin| plugin.on_map_request(ectx.clone()).unwrap());
+            .for_each(|plugin| run_plugin(|| plugin.on_leave_notify(ectx.clone())));
     }
 
     #[tracing::instrument(
         skip_all,
         fields(
-            window = ectx.event.window(),
-            atom = ectx.event.atom(),
+            event_window = ectx.event.event,
+            detail = ectx.event.detail,
+            mode = ectx.event.mode,
         )
     )]
-    pub fn on_property_notify(&mut self, ectx: EventContext<xcb::PropertyNotifyEvent>) {
+    fn on_focus_in(&mut self, ectx: EventContext<FocusEvent>) {
         self.plugins
             .iter_mut()
-            .for_each(|plugin| plugin.on_property_notify(ectx.clone()).unwrap());
+            .for_each(|plugin| run_plugin(|| plugin.on_focus_in(ectx.clone())));
     }
 
     #[tracing::instrument(
         skip_all,
         fields(
-            event_window = ectx.event.event(),
-            state = ectx.event.state(),
-            mode = ectx.event.mode(),
+            event_window = ectx.event.event,
+            detail = ectx.event.detail,
+            mode = ectx.event.mode,
         )
     )]
-    pub fn on_enter_notify(&mut self, ectx: EventContext<xcb::EnterNotifyEvent>) {
+    fn on_focus_out(&mut self, ectx: EventContext<FocusEvent>) {
         self.plugins
             .iter_mut()
-            .for_each(|plugin| plugin.on_enter_notify(ectx.clone()).unwrap());
+            .for_each(|plugin| run_plugin(|| plugin.on_focus_out(ectx.clone())));
     }
 
-    #[tracing::instrument(skip_all, fields(window = ectx.event.window()))]
-    pub fn on_unmap_notify(&mut self, ectx: EventContext<xcb::UnmapNotifyEvent>) {
+    #[tracing::instrument(
+        skip_all,
+        fields(
+            window = ectx.event.window,
+            count = ectx.event.count,
+        )
+    )]
+    fn on_expose(&mut self, ectx: EventContext<ExposeEvent>) {
         self.plugins
             .iter_mut()
-            .for_each(|plugin| plugin.on_unmap_notify(ectx.clone()).unwrap());
+            .for_each(|plugin| run_plugin(|| plugin.on_expose(ectx.clone())));
     }
 
-    #[tracing::instrument(skip_all, fields(window = ectx.event.window()))]
-    pub fn on_destroy_notify(&mut self, ectx: EventContext<xcb::DestroyNotifyEvent>) {
+    #[tracing::instrument(skip_all, fields(window = ectx.event.window))]
+    fn on_unmap_notify(&mut self, ectx: EventContext<UnmapNotifyEvent>) {
+        self.plugins
+            .iter_mut()
+            .for_each(|plugin| run_plugin(|| plugin.on_unmap_notify(ectx.clone())));
+    }
+
+    #[tracing::instrument(skip_all, fields(window = ectx.event.window))]
+    fn on_destroy_notify(&mut self, ectx: EventContext<DestroyNotifyEvent>) {
+        self.plugins
+            .iter_mut()
+            .for_each(|plugin| run_plugin(|| plugin.on_destroy_notify(ectx.clone())));
+    }
+
+    #[tracing::instrument(
+        skip_all,
+        fields(
+            root = ectx.event.root,
+            width = ectx.event.width,
+            height = ectx.event.height,
+        )
+    )]
+    fn on_randr_screen_change_notify(&mut self, ectx: EventContext<RandrScreenChangeNotifyEvent>) {
+        self.plugins
+            .iter_mut()
+            .for_each(|plugin| run_plugin(|| plugin.on_randr_screen_change_notify(ectx.clone())));
+    }
+
+    #[tracing::instrument(skip_all, fields(sub_code = ectx.event.sub_code))]
+    fn on_randr_notify(&mut self, ectx: EventContext<RandrNotifyEvent>) {
+        self.plugins
+            .iter_mut()
+            .for_each(|plugin| run_plugin(|| plugin.on_randr_notify(ectx.clone())));
+    }
+
+    #[tracing::instrument(skip_all, fields(device_id = ectx.event.device_id))]
+    fn on_xkb_new_keyboard_notify(&mut self, ectx: EventContext<XkbNewKeyboardNotifyEvent>) {
+        self.plugins
+            .iter_mut()
+            .for_each(|plugin| run_plugin(|| plugin.on_xkb_new_keyboard_notify(ectx.clone())));
+    }
+
+    #[tracing::instrument(skip_all, fields(device_id = ectx.event.device_id))]
+    fn on_xkb_map_notify(&mut self, ectx: EventContext<XkbMapNotifyEvent>) {
+        self.plugins
+            .iter_mut()
+            .for_each(|plugin| run_plugin(|| plugin.on_xkb_map_notify(ectx.clone())));
+    }
+
+    #[tracing::instrument(
+        skip_all,
+        fields(
+            device_id = ectx.event.device_id,
+            mods = ectx.event.mods,
+        )
+    )]
+    fn on_xkb_state_notify(&mut self, ectx: EventContext<XkbStateNotifyEvent>) {
+        self.plugins
+            .iter_mut()
+            .for_each(|plugin| run_plugin(|| plugin.on_xkb_state_notify(ectx.clone())));
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn on_startup(&mut self, ectx: EventContext<()>) {
+        self.plugins
+            .iter_mut()
+            .for_each(|plugin| run_plugin(|| plugin.on_startup(ectx.clone())));
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn on_shutdown(&mut self, ectx: EventContext<()>) {
+        self.plugins
+            .iter_mut()
+            .for_each(|plugin| run_plugin(|| plugin.on_shutdown(ectx.clone())));
+    }
+
+    #[tracing::instrument(
+        skip_all,
+        fields(
+            window = ectx.event.window,
+            workspace = ectx.event.workspace,
+        )
+    )]
+    fn on_window_created(&mut self, ectx: EventContext<WindowCreatedEvent>) {
+        self.plugins
+            .iter_mut()
+            .for_each(|plugin| run_plugin(|| plugin.on_window_created(ectx.clone())));
+    }
+
+    #[tracing::instrument(
+        skip_all,
+        fields(
+            workspace = ectx.event.workspace,
+            previous = ectx.event.previous,
+        )
+    )]
+    fn on_workspace_changed(&mut self, ectx: EventContext<WorkspaceChangedEvent>) {
+        self.plugins
+            .iter_mut()
+            .for_each(|plugin| run_plugin(|| plugin.on_workspace_changed(ectx.clone())));
+    }
+
+    #[tracing::instrument(
+        skip_all,
+        fields(
+            window = ectx.event.window,
+            workspace = ectx.event.workspace,
+        )
+    )]
+    fn on_window_destroyed(&mut self, ectx: EventContext<WindowDestroyedEvent>) {
+        self.plugins
+            .iter_mut()
+            .for_each(|plugin| run_plugin(|| plugin.on_window_destroyed(ectx.clone())));
+    }
+
+    #[tracing::instrument(
+        skip_all,
+        fields(
+            window = ectx.event.window,
+            previous = ectx.event.previous,
+        )
+    )]
+    fn on_focus_changed(&mut self, ectx: EventContext<FocusChangedEvent>) {
+        self.plugins
+            .iter_mut()
+            .for_each(|plugin| run_plugin(|| plugin.on_focus_changed(ectx.clone())));
+    }
+
+    #[tracing::instrument(
+        skip_all,
+        fields(
+            window = ectx.event.window,
+            full_screen = ectx.event.full_screen,
+        )
+    )]
+    fn on_full_screen(&mut self, ectx: EventContext<FullScreenEvent>) {
         self.plugins
             .iter_mut()
-            .for_each(|plugin| plugin.on_destroy_notify(ectx.clone()).unwrap());
+            .for_each(|plugin| run_plugin(|| plugin.on_full_screen(ectx.clone())));
     }
 }
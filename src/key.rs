@@ -1,3 +1,11 @@
+// Modifiers that are held down as part of a binding, lock and num lock are left out
+pub const MODIFIER_MASK: u16 = (xcb::MOD_MASK_SHIFT
+    | xcb::MOD_MASK_CONTROL
+    | xcb::MOD_MASK_1
+    | xcb::MOD_MASK_3
+    | xcb::MOD_MASK_4
+    | xcb::MOD_MASK_5) as u16;
+
 pub fn grab_key(
     conn: &xcb_util::ewmh::Connection,
     modifier: u16,
@@ -22,3 +30,19 @@ pub fn grab_key(
         }
     }
 }
+
+// The modifier mask a key sets when held, zero when the key is not a modifier
+pub fn modifier_mask(conn: &xcb_util::ewmh::Connection, keycode: xcb::Keycode) -> u16 {
+    let reply = match xcb::get_modifier_mapping(conn).get_reply() {
+        Ok(reply) => reply,
+        Err(_) => return 0,
+    };
+
+    let per_modifier = reply.keycodes_per_modifier() as usize;
+
+    reply
+        .keycodes()
+        .iter()
+        .position(|&k| k == keycode && k != 0)
+        .map_or(0, |i| 1 << (i / per_modifier))
+}
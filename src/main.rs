@@ -1,3 +1,4 @@
+mod autostart;
 mod client;
 mod config;
 mod event;
@@ -5,6 +6,8 @@ mod handler;
 mod key;
 mod plugin;
 mod plugins;
+mod process;
+mod property;
 mod screen;
 mod window_manager;
 
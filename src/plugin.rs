@@ -1,32 +1,107 @@
-use crate::event::EventContext;
+use crate::event::*;
 use anyhow::Result;
 
 pub trait PluginHandler {
-    fn on_client_message(&mut self, _ectx: EventContext<xcb::ClientMessageEvent>) -> Result<()> {
+    fn on_event(&mut self, _ectx: EventContext<Event>) -> Result<()> {
         Ok(())
     }
-    fn on_key_press(&mut self, _ectx: EventContext<xcb::KeyPressEvent>) -> Result<()> {
+    fn on_client_message(&mut self, _ectx: EventContext<ClientMessageEvent>) -> Result<()> {
         Ok(())
     }
-    fn on_configure_request(
+    fn on_key_press(&mut self, _ectx: EventContext<KeyEvent>) -> Result<()> {
+        Ok(())
+    }
+    fn on_key_release(&mut self, _ectx: EventContext<KeyEvent>) -> Result<()> {
+        Ok(())
+    }
+    fn on_button_press(&mut self, _ectx: EventContext<ButtonEvent>) -> Result<()> {
+        Ok(())
+    }
+    fn on_button_release(&mut self, _ectx: EventContext<ButtonEvent>) -> Result<()> {
+        Ok(())
+    }
+    fn on_motion_notify(&mut self, _ectx: EventContext<MotionNotifyEvent>) -> Result<()> {
+        Ok(())
+    }
+    fn on_configure_request(&mut self, _ectx: EventContext<ConfigureRequestEvent>) -> Result<()> {
+        Ok(())
+    }
+    fn on_configure_notify(&mut self, _ectx: EventContext<ConfigureNotifyEvent>) -> Result<()> {
+        Ok(())
+    }
+    fn on_create_notify(&mut self, _ectx: EventContext<CreateNotifyEvent>) -> Result<()> {
+        Ok(())
+    }
+    fn on_map_request(&mut self, _ectx: EventContext<MapRequestEvent>) -> Result<()> {
+        Ok(())
+    }
+    fn on_mapping_notify(&mut self, _ectx: EventContext<MappingNotifyEvent>) -> Result<()> {
+        Ok(())
+    }
+    fn on_property_notify(&mut self, _ectx: EventContext<PropertyNotifyEvent>) -> Result<()> {
+        Ok(())
+    }
+    fn on_enter_notify(&mut self, _ectx: EventContext<CrossingEvent>) -> Result<()> {
+        Ok(())
+    }
+    fn on_leave_notify(&mut self, _ectx: EventContext<CrossingEvent>) -> Result<()> {
+        Ok(())
+    }
+    fn on_focus_in(&mut self, _ectx: EventContext<FocusEvent>) -> Result<()> {
+        Ok(())
+    }
+    fn on_focus_out(&mut self, _ectx: EventContext<FocusEvent>) -> Result<()> {
+        Ok(())
+    }
+    fn on_expose(&mut self, _ectx: EventContext<ExposeEvent>) -> Result<()> {
+        Ok(())
+    }
+    fn on_unmap_notify(&mut self, _ectx: EventContext<UnmapNotifyEvent>) -> Result<()> {
+        Ok(())
+    }
+    fn on_destroy_notify(&mut self, _ectx: EventContext<DestroyNotifyEvent>) -> Result<()> {
+        Ok(())
+    }
+    fn on_randr_screen_change_notify(
         &mut self,
-        _ectx: EventContext<xcb::ConfigureRequestEvent>,
+        _ectx: EventContext<RandrScreenChangeNotifyEvent>,
     ) -> Result<()> {
         Ok(())
     }
-    fn on_map_request(&mut self, _ectx: EventContext<xcb::MapRequestEvent>) -> Result<()> {
+    fn on_randr_notify(&mut self, _ectx: EventContext<RandrNotifyEvent>) -> Result<()> {
+        Ok(())
+    }
+    fn on_xkb_new_keyboard_notify(
+        &mut self,
+        _ectx: EventContext<XkbNewKeyboardNotifyEvent>,
+    ) -> Result<()> {
+        Ok(())
+    }
+    fn on_xkb_map_notify(&mut self, _ectx: EventContext<XkbMapNotifyEvent>) -> Result<()> {
+        Ok(())
+    }
+    fn on_xkb_state_notify(&mut self, _ectx: EventContext<XkbStateNotifyEvent>) -> Result<()> {
+        Ok(())
+    }
+    fn on_startup(&mut self, _ectx: EventContext<()>) -> Result<()> {
+        Ok(())
+    }
+    fn on_shutdown(&mut self, _ectx: EventContext<()>) -> Result<()> {
+        Ok(())
+    }
+    fn on_window_created(&mut self, _ectx: EventContext<WindowCreatedEvent>) -> Result<()> {
         Ok(())
     }
-    fn on_property_notify(&mut self, _ectx: EventContext<xcb::PropertyNotifyEvent>) -> Result<()> {
+    fn on_window_destroyed(&mut self, _ectx: EventContext<WindowDestroyedEvent>) -> Result<()> {
         Ok(())
     }
-    fn on_enter_notify(&mut self, _ectx: EventContext<xcb::EnterNotifyEvent>) -> Result<()> {
+    fn on_focus_changed(&mut self, _ectx: EventContext<FocusChangedEvent>) -> Result<()> {
         Ok(())
     }
-    fn on_unmap_notify(&mut self, _ectx: EventContext<xcb::UnmapNotifyEvent>) -> Result<()> {
+    fn on_workspace_changed(&mut self, _ectx: EventContext<WorkspaceChangedEvent>) -> Result<()> {
         Ok(())
     }
-    fn on_destroy_notify(&mut self, _ectx: EventContext<xcb::DestroyNotifyEvent>) -> Result<()> {
+    fn on_full_screen(&mut self, _ectx: EventContext<FullScreenEvent>) -> Result<()> {
         Ok(())
     }
 }
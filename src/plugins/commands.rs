@@ -1,4 +1,4 @@
-use crate::event::EventContext;
+use crate::event::*;
 use crate::plugin::PluginHandler;
 use anyhow::Result;
 
@@ -6,14 +6,14 @@ use anyhow::Result;
 pub struct Commands;
 
 impl PluginHandler for Commands {
-    fn on_key_press(&mut self, ectx: EventContext<xcb::KeyPressEvent>) -> Result<()> {
+    fn on_key_press(&mut self, ectx: EventContext<KeyEvent>) -> Result<()> {
         let key_symbols = xcb_util::keysyms::KeySymbols::new(&ectx.conn);
         for command in &ectx.config.commands {
             if let Some(keycode) = key_symbols.get_keycode(command.keysym).next() {
-                if keycode == ectx.event.detail() && command.modifier == ectx.event.state() {
-                    std::process::Command::new(command.command.clone())
-                        .spawn()
-                        .unwrap();
+                if keycode == ectx.event.detail && command.modifier == ectx.event.state {
+                    let mut clients = ectx.lock_clients();
+                    clients.set_user_time(ectx.event.time);
+                    clients.launch(&command.command, Some(ectx.event.time));
                 }
             }
         }
@@ -1,4 +1,4 @@
-use crate::event::EventContext;
+use crate::event::*;
 use crate::plugin::PluginHandler;
 use anyhow::Result;
 
@@ -6,9 +6,9 @@ use anyhow::Result;
 pub struct DestroyWindow;
 
 impl PluginHandler for DestroyWindow {
-    fn on_destroy_notify(&mut self, ectx: EventContext<xcb::DestroyNotifyEvent>) -> Result<()> {
-        let mut clients = ectx.clients.lock().unwrap();
-        clients.destroy(ectx.event.window());
+    fn on_destroy_notify(&mut self, ectx: EventContext<DestroyNotifyEvent>) -> Result<()> {
+        let mut clients = ectx.lock_clients();
+        clients.destroy(ectx.event.window);
 
         Ok(())
     }
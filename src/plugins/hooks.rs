@@ -0,0 +1,140 @@
+use crate::config::HookEvent;
+use crate::event::*;
+use crate::plugin::PluginHandler;
+use crate::process;
+use crate::property::{get_wm_class, get_wm_title};
+use anyhow::Result;
+use std::collections::HashMap;
+
+#[derive(Clone, Default)]
+struct WindowDetails {
+    class: String,
+    title: String,
+}
+
+// Runs the commands in the hooks section of the config when events happen
+#[derive(Default)]
+pub struct Hooks {
+    windows: HashMap<xcb::Window, WindowDetails>,
+}
+
+impl Hooks {
+    fn run<C>(&self, ectx: &EventContext<C>, event: HookEvent, env: Vec<(&str, String)>) {
+        for hook in ectx.config.hooks.iter().filter(|hook| hook.event == event) {
+            tracing::debug!("running hook; command={:?}", hook.command);
+
+            process::spawn_detached(&hook.command, &env, ectx.config.command_log.as_deref());
+        }
+    }
+
+    fn window_env(
+        &mut self,
+        conn: &xcb_util::ewmh::Connection,
+        window: xcb::Window,
+    ) -> Vec<(&'static str, String)> {
+        let details = self
+            .windows
+            .entry(window)
+            .or_insert_with(|| read_window_details(conn, window));
+
+        vec![
+            ("MWM_WINDOW", window.to_string()),
+            ("MWM_CLASS", details.class.clone()),
+            ("MWM_TITLE", details.title.clone()),
+        ]
+    }
+}
+
+fn read_window_details(conn: &xcb_util::ewmh::Connection, window: xcb::Window) -> WindowDetails {
+    WindowDetails {
+        class: get_wm_class(conn, window)
+            .map(|(_, class)| class)
+            .unwrap_or_default(),
+        title: get_wm_title(conn, window).unwrap_or_default(),
+    }
+}
+
+fn workspace_env(workspace: Option<u8>) -> (&'static str, String) {
+    (
+        "MWM_WORKSPACE",
+        workspace.map_or_else(String::new, |w| w.to_string()),
+    )
+}
+
+impl PluginHandler for Hooks {
+    fn on_startup(&mut self, ectx: EventContext<()>) -> Result<()> {
+        self.run(&ectx, HookEvent::Startup, vec![]);
+
+        Ok(())
+    }
+
+    fn on_shutdown(&mut self, ectx: EventContext<()>) -> Result<()> {
+        self.run(&ectx, HookEvent::Shutdown, vec![]);
+
+        Ok(())
+    }
+
+    fn on_property_notify(&mut self, ectx: EventContext<PropertyNotifyEvent>) -> Result<()> {
+        let window = ectx.event.window;
+
+        let is_title =
+            ectx.event.atom == ectx.conn.WM_NAME() || ectx.event.atom == xcb::ATOM_WM_NAME;
+
+        if is_title && self.windows.contains_key(&window) {
+            self.windows
+                .insert(window, read_window_details(&ectx.conn, window));
+        }
+
+        Ok(())
+    }
+
+    fn on_window_created(&mut self, ectx: EventContext<WindowCreatedEvent>) -> Result<()> {
+        let mut env = self.window_env(&ectx.conn, ectx.event.window);
+        env.push(workspace_env(ectx.event.workspace));
+
+        self.run(&ectx, HookEvent::WindowCreated, env);
+
+        Ok(())
+    }
+
+    fn on_window_destroyed(&mut self, ectx: EventContext<WindowDestroyedEvent>) -> Result<()> {
+        let mut env = self.window_env(&ectx.conn, ectx.event.window);
+        env.push(workspace_env(ectx.event.workspace));
+
+        self.windows.remove(&ectx.event.window);
+
+        self.run(&ectx, HookEvent::WindowDestroyed, env);
+
+        Ok(())
+    }
+
+    fn on_focus_changed(&mut self, ectx: EventContext<FocusChangedEvent>) -> Result<()> {
+        let mut env = match ectx.event.window {
+            Some(window) => self.window_env(&ectx.conn, window),
+            None => vec![],
+        };
+        env.push(workspace_env(Some(ectx.event.workspace)));
+
+        self.run(&ectx, HookEvent::FocusChanged, env);
+
+        Ok(())
+    }
+
+    fn on_workspace_changed(&mut self, ectx: EventContext<WorkspaceChangedEvent>) -> Result<()> {
+        let env = vec![workspace_env(Some(ectx.event.workspace))];
+
+        self.run(&ectx, HookEvent::WorkspaceChanged, env);
+
+        Ok(())
+    }
+
+    fn on_full_screen(&mut self, ectx: EventContext<FullScreenEvent>) -> Result<()> {
+        let mut env = self.window_env(&ectx.conn, ectx.event.window);
+        env.push(workspace_env(ectx.event.workspace));
+        env.push(("MWM_FULLSCREEN", ectx.event.full_screen.to_string()));
+
+        self.run(&ectx, HookEvent::Fullscreen, env);
+
+        Ok(())
+    }
+}
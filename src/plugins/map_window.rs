@@ -1,4 +1,4 @@
-use crate::event::EventContext;
+use crate::event::*;
 use crate::plugin::PluginHandler;
 use anyhow::Result;
 
@@ -6,14 +6,32 @@ use anyhow::Result;
 pub struct MapWindow;
 
 impl PluginHandler for MapWindow {
-    fn on_map_request(&mut self, ectx: EventContext<xcb::MapRequestEvent>) -> Result<()> {
-        if has_override_redirect(&ectx.conn, ectx.event.window()) {
+    fn on_map_request(&mut self, ectx: EventContext<MapRequestEvent>) -> Result<()> {
+        if has_override_redirect(&ectx.conn, ectx.event.window) {
             return Ok(());
         }
 
-        let mut clients = ectx.clients.lock().unwrap();
-        clients.create(ectx.event.window());
-        clients.show(ectx.event.window());
+        let mut clients = ectx.lock_clients();
+
+        // Applications mapping a minimized window want it back
+        if clients.minimized.contains(&ectx.event.window) {
+            clients.restore(ectx.event.window);
+            return Ok(());
+        }
+
+        // Withdrawn windows are no longer managed, so mapping one again manages it anew
+        clients.create(ectx.event.window);
+
+        let shown = clients.clients.iter().any(|c| {
+            c.window == ectx.event.window
+                && (c.workspace.is_none()
+                    || c.workspace == Some(clients.active_workspace)
+                    || c.sticky)
+        });
+
+        if shown {
+            clients.show(ectx.event.window);
+        }
 
         Ok(())
     }
@@ -1,17 +1,71 @@
 pub mod commands;
 pub mod configure_window;
 pub mod destroy_window;
+pub mod hooks;
 pub mod map_window;
+pub mod script;
 pub mod unmap_window;
+pub mod urgency;
+pub mod user_time;
 pub mod window_selector;
 pub mod window_sizer;
+pub mod window_state;
 pub mod workspaces;
 
 pub use commands::Commands;
 pub use configure_window::ConfigureWindow;
 pub use destroy_window::DestroyWindow;
+pub use hooks::Hooks;
 pub use map_window::MapWindow;
+pub use script::Script;
 pub use unmap_window::UnmapWindow;
+pub use urgency::Urgency;
+pub use user_time::UserTime;
 pub use window_selector::WindowSelector;
 pub use window_sizer::WindowSizer;
+pub use window_state::WindowState;
 pub use workspaces::Workspaces;
+
+use crate::plugin::PluginHandler;
+use anyhow::{anyhow, Context, Result};
+use serde::de::DeserializeOwned;
+
+// Plugins implementing window manager protocols, leaving them out breaks clients relying on them
+pub const PROTOCOL_PLUGINS: [&str; 3] = ["window_state", "urgency", "user_time"];
+
+pub fn from_name(
+    name: &str,
+    settings: Option<&toml::Value>,
+) -> Result<Box<dyn PluginHandler + Send>> {
+    let plugin: Box<dyn PluginHandler + Send> = match name {
+        "commands" => Box::new(Commands::default()),
+        "configure_window" => Box::new(ConfigureWindow::default()),
+        "destroy_window" => Box::new(DestroyWindow::default()),
+        "hooks" => Box::new(Hooks::default()),
+        "map_window" => Box::new(MapWindow::default()),
+        "script" => Box::new(Script::new(parse_settings(name, settings)?)?),
+        "unmap_window" => Box::new(UnmapWindow::default()),
+        "urgency" => Box::new(Urgency::default()),
+        "user_time" => Box::new(UserTime::default()),
+        "window_selector" => Box::new(WindowSelector::default()),
+        "window_sizer" => Box::new(WindowSizer::default()),
+        "window_state" => Box::new(WindowState::default()),
+        "workspaces" => Box::new(Workspaces::new(parse_settings(name, settings)?)),
+        _ => return Err(anyhow!("Unknown plugin: {}", name)),
+    };
+
+    Ok(plugin)
+}
+
+fn parse_settings<T: DeserializeOwned + Default>(
+    name: &str,
+    settings: Option<&toml::Value>,
+) -> Result<T> {
+    match settings {
+        Some(settings) => settings
+            .clone()
+            .try_into()
+            .with_context(|| format!("Unable to parse settings for plugin: {}", name)),
+        None => Ok(T::default()),
+    }
+}
@@ -0,0 +1,322 @@
+use crate::client::Clients;
+use crate::config::{expand, Action, CommandLine};
+use crate::event::*;
+use crate::key::grab_key;
+use crate::plugin::PluginHandler;
+use crate::screen::get_screen;
+use anyhow::Result;
+use rhai::{Array, Dynamic, Engine, Scope, AST, INT};
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct ScriptConfig {
+    // Path of the script, defaults to ~/.config/mwm/init.rhai
+    pub path: Option<String>,
+}
+
+#[derive(Clone)]
+struct KeyBinding {
+    modifier: u16,
+    keysym: u32,
+    callback: String,
+}
+
+// Handle passed to script functions to query and change the window manager
+#[derive(Clone)]
+struct ScriptApi {
+    clients: Arc<Mutex<Clients>>,
+}
+
+impl ScriptApi {
+    fn active_window(&mut self) -> Dynamic {
+        match Clients::lock(&self.clients).active_window() {
+            Some(window) => Dynamic::from(window as INT),
+            None => Dynamic::UNIT,
+        }
+    }
+
+    fn active_workspace(&mut self) -> INT {
+        Clients::lock(&self.clients).active_workspace as INT
+    }
+
+    fn windows(&mut self) -> Array {
+        Clients::lock(&self.clients)
+            .clients
+            .iter()
+            .map(|c| Dynamic::from(c.window as INT))
+            .collect()
+    }
+
+    fn window_workspace(&mut self, window: INT) -> Dynamic {
+        Clients::lock(&self.clients)
+            .clients
+            .iter()
+            .find(|c| c.window as INT == window)
+            .and_then(|c| c.workspace)
+            .map_or(Dynamic::UNIT, |workspace| Dynamic::from(workspace as INT))
+    }
+
+    fn focus(&mut self, window: INT) {
+        let mut clients = Clients::lock(&self.clients);
+        clients.set_active_window(Some(window as xcb::Window));
+    }
+
+    fn minimize(&mut self, window: INT) {
+        let mut clients = Clients::lock(&self.clients);
+        clients.minimize(window as xcb::Window);
+    }
+
+    fn restore(&mut self, window: INT) {
+        let mut clients = Clients::lock(&self.clients);
+        clients.restore(window as xcb::Window);
+    }
+
+    fn minimized(&mut self) -> Array {
+        Clients::lock(&self.clients)
+            .minimized
+            .iter()
+            .map(|&window| Dynamic::from(window as INT))
+            .collect()
+    }
+
+    fn set_workspace(&mut self, workspace: INT) {
+        if let Some(workspace) = workspace_number(workspace) {
+            let mut clients = Clients::lock(&self.clients);
+            clients.set_active_workspace(workspace);
+        }
+    }
+
+    fn move_to_workspace(&mut self, window: INT, workspace: INT) {
+        if let Some(workspace) = workspace_number(workspace) {
+            let mut clients = Clients::lock(&self.clients);
+            clients.set_window_workspace(window as xcb::Window, Some(workspace));
+        }
+    }
+
+    fn set_front_window_ratio(&mut self, ratio: f64) {
+        let mut clients = Clients::lock(&self.clients);
+        let workspace = clients.active_workspace;
+
+        clients
+            .front_window_ratio
+            .insert(workspace, ratio.clamp(0.1, 0.9) as f32);
+        clients.resize();
+    }
+
+    fn set_front_window_count(&mut self, count: INT) {
+        let mut clients = Clients::lock(&self.clients);
+        let workspace = clients.active_workspace;
+
+        clients
+            .front_window_count
+            .insert(workspace, count.max(0) as usize);
+        clients.resize();
+    }
+
+    fn action(&mut self, name: &str) {
+        match toml::Value::String(name.to_string()).try_into::<Action>() {
+            Ok(action) => {
+                let mut clients = Clients::lock(&self.clients);
+                let window = clients.active_window().unwrap_or(xcb::WINDOW_NONE);
+                clients.handle_action(window, action);
+            }
+            Err(_) => tracing::warn!("unknown action in script; action={}", name),
+        }
+    }
+
+    fn spawn(&mut self, command: &str) {
+        Clients::lock(&self.clients).launch(&CommandLine::Shell(command.to_string()), None);
+    }
+}
+
+fn workspace_number(workspace: INT) -> Option<u8> {
+    if (1..=9).contains(&workspace) {
+        Some(workspace as u8)
+    } else {
+        tracing::warn!("invalid workspace in script; workspace={}", workspace);
+        None
+    }
+}
+
+// Limits for each script call
+const MAX_OPERATIONS: u64 = 1_000_000;
+const MAX_CALL_LEVELS: usize = 64;
+
+pub struct Script {
+    engine: Engine,
+    ast: Option<AST>,
+    scope: Scope<'static>,
+    bindings: Vec<KeyBinding>,
+}
+
+impl Script {
+    pub fn new(config: ScriptConfig) -> Result<Self> {
+        let path = PathBuf::from(expand(
+            config.path.as_deref().unwrap_or("~/.config/mwm/init.rhai"),
+        ));
+
+        let bindings = Arc::new(Mutex::new(Vec::new()));
+        let mut engine = Engine::new();
+
+        // Scripts run while events are handled, a script that loops would freeze the window manager
+        engine
+            .set_max_operations(MAX_OPERATIONS)
+            .set_max_call_levels(MAX_CALL_LEVELS);
+
+        engine
+            .register_type_with_name::<ScriptApi>("Mwm")
+            .register_fn("active_window", ScriptApi::active_window)
+            .register_fn("active_workspace", ScriptApi::active_workspace)
+            .register_fn("windows", ScriptApi::windows)
+            .register_fn("window_workspace", ScriptApi::window_workspace)
+            .register_fn("focus", ScriptApi::focus)
+            .register_fn("minimize", ScriptApi::minimize)
+            .register_fn("restore", ScriptApi::restore)
+            .register_fn("minimized", ScriptApi::minimized)
+            .register_fn("set_workspace", ScriptApi::set_workspace)
+            .register_fn("move_to_workspace", ScriptApi::move_to_workspace)
+            .register_fn("set_front_window_ratio", ScriptApi::set_front_window_ratio)
+            .register_fn("set_front_window_count", ScriptApi::set_front_window_count)
+            .register_fn("action", ScriptApi::action)
+            .register_fn("spawn", ScriptApi::spawn);
+
+        let script_bindings = bindings.clone();
+        engine.register_fn(
+            "bind_key",
+            move |modifier: INT, keysym: INT, callback: &str| {
+                script_bindings.lock().unwrap().push(KeyBinding {
+                    modifier: modifier as u16,
+                    keysym: keysym as u32,
+                    callback: callback.to_string(),
+                });
+            },
+        );
+
+        let mut scope = Scope::new();
+
+        if !path.exists() {
+            tracing::info!("no script found; path={:?}", path);
+
+            return Ok(Self::without_script(engine, scope));
+        }
+
+        // A broken script is logged rather than keeping the window manager from starting
+        let ast = match engine.compile_file(path.clone()) {
+            Ok(ast) => ast,
+            Err(e) => {
+                tracing::error!("unable to compile script; path={:?}; error={}", path, e);
+                return Ok(Self::without_script(engine, scope));
+            }
+        };
+
+        // Run the top level of the script to register key bindings
+        if let Err(e) = engine.run_ast_with_scope(&mut scope, &ast) {
+            tracing::error!("unable to run script; path={:?}; error={}", path, e);
+            return Ok(Self::without_script(engine, Scope::new()));
+        }
+
+        let bindings = bindings.lock().unwrap().clone();
+
+        tracing::info!("loaded script; path={:?}", path);
+
+        Ok(Self {
+            engine,
+            ast: Some(ast),
+            scope,
+            bindings,
+        })
+    }
+
+    fn without_script(engine: Engine, scope: Scope<'static>) -> Self {
+        Self {
+            engine,
+            ast: None,
+            scope,
+            bindings: Vec::new(),
+        }
+    }
+
+    fn call<A: rhai::FuncArgs>(&mut self, name: &str, args: A) {
+        let ast = match &self.ast {
+            Some(ast) => ast,
+            None => return,
+        };
+
+        if !ast.iter_functions().any(|f| f.name == name) {
+            return;
+        }
+
+        if let Err(e) = self
+            .engine
+            .call_fn::<Dynamic>(&mut self.scope, ast, name, args)
+        {
+            tracing::error!("script function failed; function={}; error={}", name, e);
+        }
+    }
+}
+
+impl PluginHandler for Script {
+    fn on_startup(&mut self, ectx: EventContext<()>) -> Result<()> {
+        let screen = get_screen(&ectx.conn);
+
+        for binding in &self.bindings {
+            grab_key(&ectx.conn, binding.modifier, binding.keysym, screen.root());
+        }
+
+        let api = ScriptApi {
+            clients: ectx.clients,
+        };
+        self.call("on_startup", (api,));
+
+        Ok(())
+    }
+
+    fn on_key_press(&mut self, ectx: EventContext<KeyEvent>) -> Result<()> {
+        let key_symbols = xcb_util::keysyms::KeySymbols::new(&ectx.conn);
+
+        let callbacks = self
+            .bindings
+            .iter()
+            .filter(|binding| {
+                binding.modifier == ectx.event.state
+                    && key_symbols.get_keycode(binding.keysym).next() == Some(ectx.event.detail)
+            })
+            .map(|binding| binding.callback.clone())
+            .collect::<Vec<String>>();
+
+        drop(key_symbols);
+
+        for callback in callbacks {
+            let api = ScriptApi {
+                clients: ectx.clients.clone(),
+            };
+            self.call(&callback, (api,));
+        }
+
+        Ok(())
+    }
+
+    fn on_window_created(&mut self, ectx: EventContext<WindowCreatedEvent>) -> Result<()> {
+        let api = ScriptApi {
+            clients: ectx.clients,
+        };
+        self.call("on_window_created", (api, ectx.event.window as INT));
+
+        Ok(())
+    }
+
+    fn on_workspace_changed(&mut self, ectx: EventContext<WorkspaceChangedEvent>) -> Result<()> {
+        let api = ScriptApi {
+            clients: ectx.clients,
+        };
+        self.call(
+            "on_workspace_changed",
+            (api, ectx.event.workspace as INT, ectx.event.previous as INT),
+        );
+
+        Ok(())
+    }
+}
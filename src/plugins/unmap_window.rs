@@ -1,4 +1,4 @@
-use crate::event::EventContext;
+use crate::event::*;
 use crate::plugin::PluginHandler;
 use anyhow::Result;
 
@@ -6,9 +6,14 @@ use anyhow::Result;
 pub struct UnmapWindow;
 
 impl PluginHandler for UnmapWindow {
-    fn on_unmap_notify(&mut self, ectx: EventContext<xcb::UnmapNotifyEvent>) -> Result<()> {
-        let mut clients = ectx.clients.lock().unwrap();
-        clients.hide(ectx.event.window());
+    fn on_unmap_notify(&mut self, ectx: EventContext<UnmapNotifyEvent>) -> Result<()> {
+        // Each unmap is also reported to the root window, only handle it once
+        if ectx.event.event != ectx.event.window {
+            return Ok(());
+        }
+
+        let mut clients = ectx.lock_clients();
+        clients.unmap_notify(ectx.event.window);
 
         Ok(())
     }
@@ -0,0 +1,17 @@
+use crate::event::*;
+use crate::plugin::PluginHandler;
+use anyhow::Result;
+
+#[derive(Default)]
+pub struct Urgency;
+
+impl PluginHandler for Urgency {
+    fn on_property_notify(&mut self, ectx: EventContext<PropertyNotifyEvent>) -> Result<()> {
+        if ectx.event.atom == xcb::ATOM_WM_HINTS {
+            let mut clients = ectx.lock_clients();
+            clients.update_urgency(ectx.event.window);
+        }
+
+        Ok(())
+    }
+}
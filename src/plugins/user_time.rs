@@ -0,0 +1,23 @@
+use crate::event::*;
+use crate::plugin::PluginHandler;
+use anyhow::Result;
+
+#[derive(Default)]
+pub struct UserTime;
+
+// Record input seen by the window manager, new windows opened before it do not take focus
+impl PluginHandler for UserTime {
+    fn on_key_press(&mut self, ectx: EventContext<KeyEvent>) -> Result<()> {
+        let mut clients = ectx.lock_clients();
+        clients.set_user_time(ectx.event.time);
+
+        Ok(())
+    }
+
+    fn on_button_press(&mut self, ectx: EventContext<ButtonEvent>) -> Result<()> {
+        let mut clients = ectx.lock_clients();
+        clients.set_user_time(ectx.event.time);
+
+        Ok(())
+    }
+}
@@ -1,4 +1,6 @@
-use crate::event::EventContext;
+use crate::config::FocusModel;
+use crate::event::*;
+use crate::key::{modifier_mask, MODIFIER_MASK};
 use crate::plugin::PluginHandler;
 use anyhow::Result;
 
@@ -6,27 +8,82 @@ use anyhow::Result;
 pub struct WindowSelector;
 
 impl PluginHandler for WindowSelector {
-    fn on_key_press(&mut self, ectx: EventContext<xcb::KeyPressEvent>) -> Result<()> {
+    fn on_key_press(&mut self, ectx: EventContext<KeyEvent>) -> Result<()> {
         let key_symbols = xcb_util::keysyms::KeySymbols::new(&ectx.conn);
 
         for action_key_press in ectx.config.actions.iter() {
-            let keycode = key_symbols
-                .get_keycode(action_key_press.keysym)
-                .next()
-                .expect("Unknown keycode found in window_selector plugin.");
-
-            if keycode == ectx.event.detail() && action_key_press.modifier == ectx.event.state() {
-                let mut clients = ectx.clients.lock().unwrap();
-                clients.handle_action(ectx.event.event(), action_key_press.action.clone());
+            let keycode = key_symbols.get_keycode(action_key_press.keysym).next();
+
+            if keycode == Some(ectx.event.detail) && action_key_press.modifier == ectx.event.state {
+                let mut clients = ectx.lock_clients();
+                clients.handle_action(ectx.event.event, action_key_press.action.clone());
             }
         }
 
         Ok(())
     }
 
-    fn on_enter_notify(&mut self, ectx: EventContext<xcb::EnterNotifyEvent>) -> Result<()> {
-        let mut clients = ectx.clients.lock().unwrap();
-        clients.set_active_window(Some(ectx.event.event()));
+    fn on_key_release(&mut self, ectx: EventContext<KeyEvent>) -> Result<()> {
+        let mut clients = ectx.lock_clients();
+
+        if clients.focus_cycle.is_none() {
+            return Ok(());
+        }
+
+        // The state is from before the release, so remove the released modifier
+        let released = modifier_mask(&ectx.conn, ectx.event.detail);
+        let held = ectx.event.state & !released & MODIFIER_MASK;
+
+        if released != 0 && held == 0 {
+            clients.end_cycle();
+        }
+
+        Ok(())
+    }
+
+    fn on_button_press(&mut self, ectx: EventContext<ButtonEvent>) -> Result<()> {
+        if ectx.config.focus_model != FocusModel::Click {
+            return Ok(());
+        }
+
+        let mut clients = ectx.lock_clients();
+        clients.set_active_window(Some(ectx.event.event));
+
+        Ok(())
+    }
+
+    fn on_enter_notify(&mut self, ectx: EventContext<CrossingEvent>) -> Result<()> {
+        // Ignore crossings caused by grabs or moving into a child window
+        if ectx.config.focus_model == FocusModel::Click
+            || ectx.event.mode != xcb::NOTIFY_MODE_NORMAL as u8
+            || ectx.event.detail == xcb::NOTIFY_DETAIL_INFERIOR as u8
+        {
+            return Ok(());
+        }
+
+        let mut clients = ectx.lock_clients();
+        clients.set_active_window(Some(ectx.event.event));
+
+        Ok(())
+    }
+
+    fn on_leave_notify(&mut self, ectx: EventContext<CrossingEvent>) -> Result<()> {
+        if ectx.config.focus_model != FocusModel::FollowsMouse
+            || ectx.event.mode != xcb::NOTIFY_MODE_NORMAL as u8
+            || ectx.event.detail == xcb::NOTIFY_DETAIL_INFERIOR as u8
+        {
+            return Ok(());
+        }
+
+        // Only drop focus when the pointer is over the root window, not another window
+        let pointer = xcb::query_pointer(&ectx.conn, ectx.event.root).get_reply();
+
+        if let Ok(pointer) = pointer {
+            if pointer.child() == xcb::NONE {
+                let mut clients = ectx.lock_clients();
+                clients.set_active_window(None);
+            }
+        }
 
         Ok(())
     }
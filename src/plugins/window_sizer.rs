@@ -1,4 +1,4 @@
-use crate::event::EventContext;
+use crate::event::*;
 use crate::plugin::PluginHandler;
 use anyhow::Result;
 
@@ -6,24 +6,24 @@ use anyhow::Result;
 pub struct WindowSizer;
 
 impl PluginHandler for WindowSizer {
-    fn on_property_notify(&mut self, ectx: EventContext<xcb::PropertyNotifyEvent>) -> Result<()> {
-        if ectx.event.atom() == ectx.conn.WM_STRUT_PARTIAL() {
-            let mut clients = ectx.clients.lock().unwrap();
+    fn on_property_notify(&mut self, ectx: EventContext<PropertyNotifyEvent>) -> Result<()> {
+        if ectx.event.atom == ectx.conn.WM_STRUT_PARTIAL() {
+            let mut clients = ectx.lock_clients();
             clients.resize();
         }
 
         Ok(())
     }
 
-    fn on_map_request(&mut self, ectx: EventContext<xcb::MapRequestEvent>) -> Result<()> {
-        let mut clients = ectx.clients.lock().unwrap();
+    fn on_map_request(&mut self, ectx: EventContext<MapRequestEvent>) -> Result<()> {
+        let mut clients = ectx.lock_clients();
         clients.resize();
 
         Ok(())
     }
 
-    fn on_unmap_notify(&mut self, ectx: EventContext<xcb::UnmapNotifyEvent>) -> Result<()> {
-        let mut clients = ectx.clients.lock().unwrap();
+    fn on_unmap_notify(&mut self, ectx: EventContext<UnmapNotifyEvent>) -> Result<()> {
+        let mut clients = ectx.lock_clients();
         clients.resize();
 
         Ok(())
@@ -0,0 +1,43 @@
+use crate::event::*;
+use crate::plugin::PluginHandler;
+use crate::property::{intern_atom, WM_STATE_ICONIC};
+use anyhow::Result;
+
+#[derive(Default)]
+pub struct WindowState;
+
+impl PluginHandler for WindowState {
+    fn on_client_message(&mut self, ectx: EventContext<ClientMessageEvent>) -> Result<()> {
+        let window = ectx.event.window;
+
+        // Add, remove or toggle up to two states at once
+        if ectx.event.type_ == ectx.conn.WM_STATE() {
+            let data = ectx.event.data32();
+
+            let mut clients = ectx.lock_clients();
+            clients.change_state(window, data[0], data[1]);
+            clients.change_state(window, data[0], data[2]);
+        }
+
+        // Applications asking to be minimized
+        if ectx.event.type_ == intern_atom(&ectx.conn, "WM_CHANGE_STATE")
+            && ectx.event.data32()[0] == WM_STATE_ICONIC
+        {
+            let mut clients = ectx.lock_clients();
+            clients.minimize(window);
+        }
+
+        // Pagers and tools such as `xdotool windowactivate` asking for a window to be focused
+        if ectx.event.type_ == ectx.conn.ACTIVE_WINDOW() {
+            let mut clients = ectx.lock_clients();
+
+            if clients.minimized.contains(&window) {
+                clients.restore(window);
+            } else {
+                clients.focus_window(window);
+            }
+        }
+
+        Ok(())
+    }
+}
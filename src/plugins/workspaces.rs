@@ -1,31 +1,45 @@
-use crate::event::EventContext;
+use crate::event::*;
 use crate::plugin::PluginHandler;
 use anyhow::Result;
+use serde::Deserialize;
 
-#[derive(Default)]
-pub struct Workspaces;
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct WorkspacesConfig {
+    // Switch to the workspace a window is moved to
+    pub follow_moved_window: bool,
+}
+
+pub struct Workspaces {
+    config: WorkspacesConfig,
+}
+
+impl Workspaces {
+    pub fn new(config: WorkspacesConfig) -> Self {
+        Self { config }
+    }
+}
 
 impl PluginHandler for Workspaces {
-    fn on_client_message(&mut self, ectx: EventContext<xcb::ClientMessageEvent>) -> Result<()> {
-        if ectx.event.type_() == ectx.conn.CURRENT_DESKTOP() {
-            let mut clients = ectx.clients.lock().unwrap();
+    fn on_client_message(&mut self, ectx: EventContext<ClientMessageEvent>) -> Result<()> {
+        if ectx.event.type_ == ectx.conn.CURRENT_DESKTOP() {
+            let mut clients = ectx.lock_clients();
             clients.set_active_workspace(1);
         }
 
         Ok(())
     }
 
-    fn on_key_press(&mut self, ectx: EventContext<xcb::KeyPressEvent>) -> Result<()> {
+    fn on_key_press(&mut self, ectx: EventContext<KeyEvent>) -> Result<()> {
         let key_symbols = xcb_util::keysyms::KeySymbols::new(&ectx.conn);
 
         let mut active_workspace: Option<u8> = None;
         for workspace in 1..=9 {
             let keycode = key_symbols
                 .get_keycode(x11::keysym::XK_0 + workspace as u32)
-                .next()
-                .expect("Unknown keycode found in workspaces plugin.");
+                .next();
 
-            if keycode == ectx.event.detail() {
+            if keycode == Some(ectx.event.detail) {
                 active_workspace = Some(workspace);
                 break;
             }
@@ -34,14 +48,22 @@ impl PluginHandler for Workspaces {
         drop(key_symbols);
 
         if let Some(workspace) = active_workspace {
-            let mut clients = ectx.clients.lock().unwrap();
+            let mut clients = ectx.lock_clients();
 
-            if ectx.config.workspace_modifier == ectx.event.state() {
+            if ectx.config.workspace_modifier == ectx.event.state {
                 clients.set_active_workspace(workspace);
+
+                if let Some(window) = clients.active_window() {
+                    clients.warp_pointer(window);
+                }
             }
 
-            if ectx.config.workspace_move_window_modifier == ectx.event.state() {
-                clients.set_window_workspace(ectx.event.child(), Some(workspace));
+            if ectx.config.workspace_move_window_modifier == ectx.event.state {
+                clients.set_window_workspace(ectx.event.child, Some(workspace));
+
+                if self.config.follow_moved_window {
+                    clients.set_active_workspace(workspace);
+                }
             }
         }
 
@@ -0,0 +1,115 @@
+use crate::config::{expand, CommandLine};
+use anyhow::{anyhow, Context, Result};
+use std::fs::{self, File, OpenOptions};
+use std::path::PathBuf;
+use std::process::Stdio;
+use tokio::process::{Child, Command};
+
+fn log_path(command_log: Option<&str>) -> Option<PathBuf> {
+    if let Some(path) = command_log {
+        return Some(PathBuf::from(expand(path)));
+    }
+
+    let state_path = match std::env::var_os("XDG_STATE_HOME") {
+        Some(path) => PathBuf::from(path),
+        None => PathBuf::from(std::env::var_os("HOME")?).join(".local/state"),
+    };
+
+    Some(state_path.join("mwm/commands.log"))
+}
+
+fn open_log(command_log: Option<&str>) -> Option<File> {
+    let path = log_path(command_log)?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).ok()?;
+    }
+
+    OpenOptions::new().create(true).append(true).open(path).ok()
+}
+
+fn build(command: &CommandLine) -> Result<Command> {
+    let mut cmd = match command {
+        CommandLine::Shell(line) => {
+            let mut cmd = Command::new("sh");
+            cmd.arg("-c").arg(line);
+            cmd
+        }
+        CommandLine::Args(args) => {
+            let mut args = args.iter().map(|arg| expand(arg));
+            let program = args.next().ok_or_else(|| anyhow!("Empty command."))?;
+
+            let mut cmd = Command::new(program);
+            cmd.args(args);
+            cmd
+        }
+    };
+
+    // Detach from the window manager's session so the process outlives it
+    unsafe {
+        cmd.pre_exec(|| {
+            libc::setsid();
+            Ok(())
+        });
+    }
+
+    Ok(cmd)
+}
+
+/// Spawns a command with its output written to the command log.
+pub fn spawn(
+    command: &CommandLine,
+    envs: &[(&str, String)],
+    cwd: Option<&str>,
+    command_log: Option<&str>,
+) -> Result<Child> {
+    let mut cmd = build(command)?;
+
+    cmd.envs(envs.iter().map(|(key, value)| (key, value)))
+        .stdin(Stdio::null());
+
+    if let Some(cwd) = cwd {
+        cmd.current_dir(expand(cwd));
+    }
+
+    match open_log(command_log) {
+        Some(log) => {
+            let stderr = log.try_clone()?;
+            cmd.stdout(log).stderr(stderr);
+        }
+        None => {
+            cmd.stdout(Stdio::null()).stderr(Stdio::null());
+        }
+    }
+
+    cmd.spawn()
+        .with_context(|| format!("Unable to spawn command: {:?}", command))
+}
+
+/// Spawns a command and reaps it in the background once it exits, failures are logged.
+/// Returns the process ID of the command.
+pub fn spawn_detached(
+    command: &CommandLine,
+    envs: &[(&str, String)],
+    command_log: Option<&str>,
+) -> Option<u32> {
+    let mut child = match spawn(command, envs, None, command_log) {
+        Ok(child) => child,
+        Err(e) => {
+            tracing::error!("{:#}", e);
+            return None;
+        }
+    };
+
+    let pid = child.id();
+    tracing::debug!("spawned command; command={:?}; pid={:?}", command, pid);
+
+    tokio::spawn(async move {
+        match child.wait().await {
+            Ok(status) => tracing::debug!("command exited; pid={:?}; status={}", pid, status),
+            Err(e) => tracing::error!("unable to wait for command; pid={:?}; error={}", pid, e),
+        }
+    });
+
+    pid
+}
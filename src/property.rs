@@ -0,0 +1,202 @@
+pub fn intern_atom(conn: &xcb::Connection, name: &str) -> xcb::Atom {
+    xcb::intern_atom(conn, false, name)
+        .get_reply()
+        .map(|reply| reply.atom())
+        .unwrap_or(xcb::ATOM_NONE)
+}
+
+pub fn get_wm_class(
+    conn: &xcb_util::ewmh::Connection,
+    window: xcb::Window,
+) -> Option<(String, String)> {
+    let reply = xcb::get_property(
+        conn,
+        false,
+        window,
+        xcb::ATOM_WM_CLASS,
+        xcb::ATOM_STRING,
+        0,
+        u32::MAX,
+    )
+    .get_reply()
+    .ok()?;
+
+    // WM_CLASS is the instance and class as two null terminated strings
+    let mut values = reply
+        .value::<u8>()
+        .split(|&b| b == 0)
+        .map(|value| String::from_utf8_lossy(value).into_owned());
+
+    Some((values.next()?, values.next().unwrap_or_default()))
+}
+
+pub fn get_wm_title(conn: &xcb_util::ewmh::Connection, window: xcb::Window) -> Option<String> {
+    let reply = xcb::get_property(
+        conn,
+        false,
+        window,
+        conn.WM_NAME(),
+        xcb::ATOM_ANY,
+        0,
+        u32::MAX,
+    )
+    .get_reply()
+    .ok()
+    .filter(|reply| reply.value_len() > 0);
+
+    // Fallback to the ICCCM name when there is no EWMH name
+    let reply = match reply {
+        Some(reply) => reply,
+        None => xcb::get_property(
+            conn,
+            false,
+            window,
+            xcb::ATOM_WM_NAME,
+            xcb::ATOM_ANY,
+            0,
+            u32::MAX,
+        )
+        .get_reply()
+        .ok()?,
+    };
+
+    Some(String::from_utf8_lossy(reply.value::<u8>()).into_owned())
+}
+
+pub fn get_wm_transient_for(
+    conn: &xcb_util::ewmh::Connection,
+    window: xcb::Window,
+) -> Option<xcb::Window> {
+    xcb::get_property(
+        conn,
+        false,
+        window,
+        xcb::ATOM_WM_TRANSIENT_FOR,
+        xcb::ATOM_WINDOW,
+        0,
+        1,
+    )
+    .get_reply()
+    .ok()
+    .and_then(|reply| reply.value::<xcb::Window>().first().copied())
+    .filter(|&parent| parent != xcb::NONE && parent != window)
+}
+
+fn get_cardinal(
+    conn: &xcb_util::ewmh::Connection,
+    window: xcb::Window,
+    property: xcb::Atom,
+) -> Option<u32> {
+    xcb::get_property(conn, false, window, property, xcb::ATOM_CARDINAL, 0, 1)
+        .get_reply()
+        .ok()
+        .and_then(|reply| reply.value::<u32>().first().copied())
+}
+
+// Time of the last user input in a window, read from its user time window when it has one
+pub fn get_wm_user_time(
+    conn: &xcb_util::ewmh::Connection,
+    window: xcb::Window,
+) -> Option<xcb::Timestamp> {
+    let time_window = xcb::get_property(
+        conn,
+        false,
+        window,
+        conn.WM_USER_TIME_WINDOW(),
+        xcb::ATOM_WINDOW,
+        0,
+        1,
+    )
+    .get_reply()
+    .ok()
+    .and_then(|reply| reply.value::<xcb::Window>().first().copied())
+    .unwrap_or(window);
+
+    get_cardinal(conn, time_window, conn.WM_USER_TIME())
+        .or_else(|| get_cardinal(conn, window, conn.WM_USER_TIME()))
+}
+
+pub fn get_startup_id(conn: &xcb_util::ewmh::Connection, window: xcb::Window) -> Option<String> {
+    let reply = xcb::get_property(
+        conn,
+        false,
+        window,
+        intern_atom(conn, "_NET_STARTUP_ID"),
+        xcb::ATOM_ANY,
+        0,
+        u32::MAX,
+    )
+    .get_reply()
+    .ok()
+    .filter(|reply| reply.value_len() > 0)?;
+
+    Some(String::from_utf8_lossy(reply.value::<u8>()).into_owned())
+}
+
+// Time of the input that launched a window, from the `_TIME` part of its startup ID
+pub fn get_startup_time(
+    conn: &xcb_util::ewmh::Connection,
+    window: xcb::Window,
+) -> Option<xcb::Timestamp> {
+    let startup_id = get_startup_id(conn, window)?;
+    let (_, time) = startup_id.rsplit_once("_TIME")?;
+
+    time.parse().ok()
+}
+
+// Whether the urgency flag of WM_HINTS is set
+pub fn get_wm_urgency(conn: &xcb_util::ewmh::Connection, window: xcb::Window) -> bool {
+    const URGENCY_HINT: u32 = 1 << 8;
+
+    xcb::get_property(
+        conn,
+        false,
+        window,
+        xcb::ATOM_WM_HINTS,
+        xcb::ATOM_WM_HINTS,
+        0,
+        1,
+    )
+    .get_reply()
+    .ok()
+    .and_then(|reply| reply.value::<u32>().first().copied())
+    .map(|flags| flags & URGENCY_HINT != 0)
+    .unwrap_or(false)
+}
+
+// ICCCM states set in WM_STATE
+pub const WM_STATE_WITHDRAWN: u32 = 0;
+pub const WM_STATE_NORMAL: u32 = 1;
+pub const WM_STATE_ICONIC: u32 = 3;
+
+pub fn set_wm_state(conn: &xcb_util::ewmh::Connection, window: xcb::Window, state: u32) {
+    let atom = intern_atom(conn, "WM_STATE");
+
+    xcb::change_property(
+        conn,
+        xcb::PROP_MODE_REPLACE as u8,
+        window,
+        atom,
+        atom,
+        32,
+        &[state, xcb::NONE],
+    );
+}
+
+// Set a UTF-8 string property on a window, used to publish state on the root window
+pub fn set_utf8_property(
+    conn: &xcb_util::ewmh::Connection,
+    window: xcb::Window,
+    name: &str,
+    value: &str,
+) {
+    xcb::change_property(
+        conn,
+        xcb::PROP_MODE_REPLACE as u8,
+        window,
+        intern_atom(conn, name),
+        intern_atom(conn, "UTF8_STRING"),
+        8,
+        value.as_bytes(),
+    );
+}
@@ -1,16 +1,21 @@
+use crate::autostart;
 use crate::client::Clients;
-use crate::config::{get_config, Config};
-use crate::event::EventContext;
+use crate::config::{get_config, Config, FocusModel};
+use crate::event::{Event, EventContext};
 use crate::handler::Handler;
 use crate::key::grab_key;
+use crate::property::{intern_atom, WM_STATE_ICONIC, WM_STATE_NORMAL};
 use crate::screen::get_screen;
-use std::sync::{Arc, Mutex};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, PoisonError};
+use tokio::signal::unix::{signal, SignalKind};
 
 pub struct WindowManager {
     clients: Arc<Mutex<Clients>>,
     config: Arc<Config>,
     conn: Arc<xcb_util::ewmh::Connection>,
     cursor: xcb::Cursor,
+    handler: Arc<Mutex<Handler>>,
 }
 
 impl WindowManager {
@@ -27,6 +32,9 @@ impl WindowManager {
 
         let clients = Arc::new(Mutex::new(Clients::new(conn.clone(), config.clone())));
 
+        let handler = Handler::new(&config).expect("Unable to load plugins.");
+        let handler = Arc::new(Mutex::new(handler));
+
         let cursor = xcb_util::cursor::create_font_cursor(&conn, xcb_util::cursor::LEFT_PTR);
 
         Self {
@@ -34,6 +42,7 @@ impl WindowManager {
             config,
             conn,
             cursor,
+            handler,
         }
     }
 
@@ -53,8 +62,31 @@ impl WindowManager {
                 self.conn.NUMBER_OF_DESKTOPS(),
                 self.conn.WM_STATE(),
                 self.conn.WM_STATE_FULLSCREEN(),
+                self.conn.WM_STATE_HIDDEN(),
+                self.conn.WM_STATE_STICKY(),
+                self.conn.WM_STATE_MAXIMIZED_VERT(),
+                self.conn.WM_STATE_MAXIMIZED_HORZ(),
+                self.conn.WM_STATE_ABOVE(),
+                self.conn.WM_STATE_BELOW(),
+                self.conn.WM_STATE_SKIP_TASKBAR(),
+                self.conn.WM_STATE_SKIP_PAGER(),
+                self.conn.WM_STATE_DEMANDS_ATTENTION(),
+                self.conn.WM_DESKTOP(),
                 self.conn.WM_WINDOW_TYPE(),
+                self.conn.WM_WINDOW_TYPE_DESKTOP(),
+                self.conn.WM_WINDOW_TYPE_DOCK(),
+                self.conn.WM_WINDOW_TYPE_TOOLBAR(),
+                self.conn.WM_WINDOW_TYPE_MENU(),
+                self.conn.WM_WINDOW_TYPE_UTILITY(),
+                self.conn.WM_WINDOW_TYPE_SPLASH(),
                 self.conn.WM_WINDOW_TYPE_DIALOG(),
+                self.conn.WM_WINDOW_TYPE_DROPDOWN_MENU(),
+                self.conn.WM_WINDOW_TYPE_POPUP_MENU(),
+                self.conn.WM_WINDOW_TYPE_TOOLTIP(),
+                self.conn.WM_WINDOW_TYPE_NOTIFICATION(),
+                self.conn.WM_WINDOW_TYPE_COMBO(),
+                self.conn.WM_WINDOW_TYPE_DND(),
+                self.conn.WM_WINDOW_TYPE_NORMAL(),
             ],
         );
 
@@ -107,7 +139,9 @@ impl WindowManager {
 
         let values = [(
             xcb::CW_EVENT_MASK,
-            xcb::EVENT_MASK_SUBSTRUCTURE_REDIRECT | xcb::EVENT_MASK_SUBSTRUCTURE_NOTIFY,
+            xcb::EVENT_MASK_SUBSTRUCTURE_REDIRECT
+                | xcb::EVENT_MASK_SUBSTRUCTURE_NOTIFY
+                | xcb::EVENT_MASK_STRUCTURE_NOTIFY,
         )];
 
         let cookie = xcb::change_window_attributes_checked(&self.conn, screen.root(), &values);
@@ -116,10 +150,39 @@ impl WindowManager {
             panic!("Unable to change window attributes. Is another window manager running?")
         }
 
-        for program in &self.config.autostart {
-            std::process::Command::new(program).spawn().unwrap();
+        // Listen for screen changes and keymap changes
+        xcb::randr::select_input(
+            &self.conn,
+            screen.root(),
+            (xcb::randr::NOTIFY_MASK_SCREEN_CHANGE
+                | xcb::randr::NOTIFY_MASK_CRTC_CHANGE
+                | xcb::randr::NOTIFY_MASK_OUTPUT_CHANGE) as u16,
+        );
+
+        let xkb_events = (xcb::xkb::EVENT_TYPE_NEW_KEYBOARD_NOTIFY
+            | xcb::xkb::EVENT_TYPE_MAP_NOTIFY
+            | xcb::xkb::EVENT_TYPE_STATE_NOTIFY) as u16;
+
+        if xcb::xkb::use_extension(&self.conn, 1, 0)
+            .get_reply()
+            .is_ok()
+        {
+            xcb::xkb::select_events(
+                &self.conn,
+                xcb::xkb::ID_USE_CORE_KBD as u16,
+                xkb_events,
+                0,
+                xkb_events,
+                0xff,
+                0xff,
+                None,
+            );
         }
 
+        self.adopt_windows(screen.root());
+
+        autostart::start(self.conn.clone(), self.config.clone());
+
         let values = [(xcb::CW_CURSOR, self.cursor)];
 
         let cookie = xcb::change_window_attributes_checked(&self.conn, screen.root(), &values);
@@ -130,97 +193,171 @@ impl WindowManager {
 
         tracing::info!("Started window manager.");
 
-        loop {
-            if let Some(event) = self.conn.wait_for_event() {
-                let clients = self.clients.clone();
-                let config = self.config.clone();
-                let conn = self.conn.clone();
+        Self::dispatch(
+            self.clients.clone(),
+            self.config.clone(),
+            self.conn.clone(),
+            self.handler.clone(),
+            Event::Startup,
+        );
+
+        self.conn.flush();
+
+        tokio::spawn(Self::shutdown_on_signal(
+            self.clients.clone(),
+            self.config.clone(),
+            self.conn.clone(),
+            self.handler.clone(),
+        ));
 
-                tokio::spawn(Self::handle(clients, config, conn, event));
+        while let Some(event) = self.conn.wait_for_event() {
+            let clients = self.clients.clone();
+            let config = self.config.clone();
+            let conn = self.conn.clone();
+            let handler = self.handler.clone();
+
+            tokio::spawn(Self::handle(clients, config, conn, handler, event));
+        }
+
+        tracing::info!("Lost connection to the X server.");
+
+        Self::dispatch(
+            self.clients.clone(),
+            self.config.clone(),
+            self.conn.clone(),
+            self.handler.clone(),
+            Event::Shutdown,
+        );
+    }
+
+    // Manage windows that already exist, such as when the window manager is restarted
+    fn adopt_windows(&self, root: xcb::Window) {
+        let tree = match xcb::query_tree(&self.conn, root).get_reply() {
+            Ok(tree) => tree,
+            Err(_) => return,
+        };
+
+        let wm_state = intern_atom(&self.conn, "WM_STATE");
+        let mut clients = Clients::lock(&self.clients);
+
+        for &window in tree.children() {
+            let attributes = match xcb::get_window_attributes(&self.conn, window).get_reply() {
+                Ok(attributes) => attributes,
+                Err(_) => continue,
+            };
+
+            if attributes.override_redirect() {
+                continue;
+            }
+
+            let state = xcb::get_property(&self.conn, false, window, wm_state, wm_state, 0, 1)
+                .get_reply()
+                .ok()
+                .and_then(|reply| reply.value::<u32>().first().copied());
+
+            let viewable = attributes.map_state() == xcb::MAP_STATE_VIEWABLE as u8;
+
+            // Windows hidden on other workspaces are unmapped but keep their workspace, windows
+            // the application withdrew, such as those closed to a tray, are left alone
+            let has_desktop = xcb_util::ewmh::get_wm_desktop(&self.conn, window)
+                .get_reply()
+                .is_ok();
+
+            let hidden = match state {
+                Some(WM_STATE_ICONIC) => true,
+                Some(WM_STATE_NORMAL) => has_desktop,
+                _ => false,
+            };
+
+            if !viewable && !hidden {
+                continue;
+            }
+
+            tracing::debug!("adopting window; window={}", window);
+
+            clients.create(window);
+
+            if state == Some(WM_STATE_ICONIC) {
+                clients.minimize(window);
             }
         }
     }
 
+    async fn shutdown_on_signal(
+        clients: Arc<Mutex<Clients>>,
+        config: Arc<Config>,
+        conn: Arc<xcb_util::ewmh::Connection>,
+        handler: Arc<Mutex<Handler>>,
+    ) {
+        let mut terminate = signal(SignalKind::terminate()).expect("Unable to listen for SIGTERM.");
+        let mut interrupt = signal(SignalKind::interrupt()).expect("Unable to listen for SIGINT.");
+
+        tokio::select! {
+            _ = terminate.recv() => (),
+            _ = interrupt.recv() => (),
+        };
+
+        tracing::info!("Stopping window manager.");
+
+        Self::dispatch(clients, config, conn.clone(), handler, Event::Shutdown);
+
+        conn.flush();
+
+        std::process::exit(0);
+    }
+
     #[tracing::instrument(skip_all, name = "event_handle")]
     async fn handle(
         clients: Arc<Mutex<Clients>>,
         config: Arc<Config>,
         conn: Arc<xcb_util::ewmh::Connection>,
+        handler: Arc<Mutex<Handler>>,
         event: xcb::GenericEvent,
     ) {
-        let mut handler = Handler::default();
-
-        let response_type = event.response_type() & !0x80;
-
-        match response_type {
-            xcb::CLIENT_MESSAGE => handler.on_client_message(EventContext {
-                clients,
-                config,
-                conn: conn.clone(),
-                event: unsafe {
-                    std::mem::transmute::<xcb::GenericEvent, xcb::ClientMessageEvent>(event)
-                },
-            }),
-            xcb::KEY_PRESS => handler.on_key_press(EventContext {
-                clients,
-                config,
-                conn: conn.clone(),
-                event: unsafe {
-                    std::mem::transmute::<xcb::GenericEvent, xcb::KeyPressEvent>(event)
-                },
-            }),
-            xcb::CONFIGURE_REQUEST => handler.on_configure_request(EventContext {
-                clients,
-                config,
-                conn: conn.clone(),
-                event: unsafe {
-                    std::mem::transmute::<xcb::GenericEvent, xcb::ConfigureRequestEvent>(event)
-                },
-            }),
-            xcb::MAP_REQUEST => handler.on_map_request(EventContext {
-                clients,
-                config,
-                conn: conn.clone(),
-                event: unsafe {
-                    std::mem::transmute::<xcb::GenericEvent, xcb::MapRequestEvent>(event)
-                },
-            }),
-            xcb::PROPERTY_NOTIFY => handler.on_property_notify(EventContext {
-                clients,
-                config,
-                conn: conn.clone(),
-                event: unsafe {
-                    std::mem::transmute::<xcb::GenericEvent, xcb::PropertyNotifyEvent>(event)
-                },
-            }),
-            xcb::ENTER_NOTIFY => handler.on_enter_notify(EventContext {
-                clients,
-                config,
-                conn: conn.clone(),
-                event: unsafe {
-                    std::mem::transmute::<xcb::GenericEvent, xcb::EnterNotifyEvent>(event)
-                },
-            }),
-            xcb::UNMAP_NOTIFY => handler.on_unmap_notify(EventContext {
-                clients,
-                config,
-                conn: conn.clone(),
-                event: unsafe {
-                    std::mem::transmute::<xcb::GenericEvent, xcb::UnmapNotifyEvent>(event)
-                },
-            }),
-            xcb::DESTROY_NOTIFY => handler.on_destroy_notify(EventContext {
-                clients,
-                config,
-                conn: conn.clone(),
-                event: unsafe {
-                    std::mem::transmute::<xcb::GenericEvent, xcb::DestroyNotifyEvent>(event)
-                },
-            }),
+        let event = match Event::decode(&conn, &event) {
+            Some(event) => event,
             // Events we do not care about
-            _ => (),
+            None => return,
+        };
+
+        // Clicks grabbed for click to focus freeze the pointer until replayed to the window
+        let replay_time = match &event {
+            Event::ButtonPress(event) if config.focus_model == FocusModel::Click => {
+                Some(event.time)
+            }
+            _ => None,
         };
 
+        Self::dispatch(clients, config, conn.clone(), handler, event);
+
+        if let Some(time) = replay_time {
+            xcb::allow_events(&conn, xcb::ALLOW_REPLAY_POINTER as u8, time);
+        }
+
         conn.flush();
     }
+
+    // Handle an event along with any events emitted while handling it
+    fn dispatch(
+        clients: Arc<Mutex<Clients>>,
+        config: Arc<Config>,
+        conn: Arc<xcb_util::ewmh::Connection>,
+        handler: Arc<Mutex<Handler>>,
+        event: Event,
+    ) {
+        // Keep handling events after a plugin panicked while holding the lock
+        let mut handler = handler.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut events = VecDeque::from(vec![event]);
+
+        while let Some(event) = events.pop_front() {
+            handler.handle(EventContext {
+                clients: clients.clone(),
+                config: config.clone(),
+                conn: conn.clone(),
+                event,
+            });
+
+            events.extend(Clients::lock(&clients).take_events());
+        }
+    }
 }